@@ -1,22 +1,14 @@
-use md5::compute;
+use santas_little_helpers::hash_search::{matches, starts_with_zeros};
 use std::time::Instant;
 
 fn solve(input: &str) -> (usize, usize) {
-    let mut output_1 = None;
-    for (i, s) in (0..)
-        .map(|s| format!("{input}{s}"))
-        .map(compute)
-        .map(|s| format!("{s:x}"))
-        .enumerate()
-    {
-        if s.starts_with("00000") {
-            output_1 = output_1.or(Some(i));
-        }
-        if s.starts_with("000000") {
-            return (output_1.unwrap(), i);
-        }
-    }
-    unreachable!()
+    let (output_1, _) = matches(input, 0, 0, |d| starts_with_zeros(d, 5))
+        .next()
+        .unwrap();
+    let (output_2, _) = matches(input, output_1, 0, |d| starts_with_zeros(d, 6))
+        .next()
+        .unwrap();
+    (output_1, output_2)
 }
 
 fn main() {
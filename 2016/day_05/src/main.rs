@@ -1,17 +1,17 @@
-use md5::compute;
+use santas_little_helpers::hash_search::{matches, nibble, starts_with_zeros, Digest};
 use std::{
     fs::read_to_string,
     io::{self, Write},
 };
 
+fn hex_digit(digest: &Digest, i: usize) -> char {
+    char::from_digit(nibble(digest, i) as u32, 16).unwrap()
+}
+
 fn generate_password_1(input: &str) -> String {
-    (0..)
-        .map(|i| format!("{input}{i}"))
-        .map(compute)
-        .map(|d| format!("{d:x}"))
-        .filter(|s| &s[..5] == "00000")
+    matches(input, 0, 0, |d| starts_with_zeros(d, 5))
         .take(8)
-        .map(|s| s.chars().nth(5).unwrap())
+        .map(|(_, d)| hex_digit(&d, 5))
         .collect()
 }
 
@@ -22,29 +22,13 @@ fn clear_screen() {
 fn generate_password_2(input: &str) -> String {
     let mut output = ['-'; 8];
     print!("{}", output.iter().collect::<String>());
-    for i in 0.. {
-        let data = format!("{input}{i}");
-        let s = format!("{:x}", compute(data));
-        let s = match s.strip_prefix("00000") {
-            Some(s) => s,
-            None => continue,
-        };
-        let index = s.chars().next().unwrap();
-        if !index.is_ascii_digit() {
-            continue;
-        }
-
-        let index = index as usize - '0' as usize;
-        if index >= 8 {
-            continue;
-        }
+    for (_, digest) in matches(input, 0, 0, |d| starts_with_zeros(d, 5) && nibble(d, 5) < 8) {
+        let index = nibble(&digest, 5) as usize;
         if output[index] != '-' {
             continue;
         }
 
-        let c = s.chars().nth(1).unwrap();
-
-        output[index] = c;
+        output[index] = hex_digit(&digest, 6);
         clear_screen();
         print!("{}", output.iter().collect::<String>());
         io::stdout().flush().unwrap();
@@ -1,199 +1,244 @@
-use itertools::Itertools;
-use priority_queue::PriorityQueue;
-use std::{cmp::Reverse, collections::HashSet, fs::read_to_string};
-
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
-enum DeviceType {
-    Microchip,
-    Generator,
-}
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+    fs::read_to_string,
+};
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Device {
-    name: String,
-    device_type: DeviceType,
+//the floors holding one element's generator and microchip
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+struct Pair {
+    generator: u8,
+    chip: u8,
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+//elements are interchangeable, so a state is just the elevator's floor and the
+//multiset of pairs. keeping the pairs sorted means states that only differ by
+//which element is which pack to the same number
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 struct State {
-    floors: [Vec<Device>; 4],
-    current_floor: usize,
+    elevator: u8,
+    pairs: Vec<Pair>,
 }
 
 impl State {
-    fn is_valid(&self) -> bool {
-        for floor in &self.floors {
-            for device in floor {
-                if device.device_type == DeviceType::Microchip {
-                    if floor
-                        .iter()
-                        .any(|d| d.name == device.name && d.device_type == DeviceType::Generator)
-                    {
-                        continue;
-                    }
-
-                    if floor.iter().any(|d| d.device_type == DeviceType::Generator) {
-                        return false;
-                    }
-                }
-            }
-        }
-
-        true
+    fn canonical(mut self) -> Self {
+        self.pairs.sort();
+        self
     }
 
-    fn apply_action(&self, action: Action) -> Option<Self> {
-        let mut output = self.clone();
-        if self.current_floor == 0 && action.direction == Direction::Down {
-            return None;
-        }
-        if self.current_floor == 3 && action.direction == Direction::Up {
-            return None;
-        }
-        for device in &action.holding {
-            output.floors[self.current_floor].retain(|d| d != device);
-        }
-        let new_floor = match action.direction {
-            Direction::Down => self.current_floor.saturating_sub(1),
-            Direction::Up => self.current_floor + 1,
-        };
-        output.floors[new_floor].extend(action.holding);
-        output.current_floor = new_floor;
-        if !output.is_valid() {
-            return None;
-        }
-
-        output.floors.iter_mut().for_each(|v| v.sort());
+    //one base floors² digit per pair after the elevator
+    fn pack(&self, floors: u8) -> u128 {
+        let base = floors as u128 * floors as u128;
+        self.pairs.iter().fold(self.elevator as u128, |acc, p| {
+            acc * base + p.generator as u128 * floors as u128 + p.chip as u128
+        })
+    }
 
-        Some(output)
+    //a chip is fried if it's with another generator and not its own
+    fn is_valid(&self) -> bool {
+        self.pairs
+            .iter()
+            .all(|p| p.generator == p.chip || self.pairs.iter().all(|q| q.generator != p.chip))
     }
 
-    fn possible_actions(&self) -> Vec<Action> {
-        self.floors[self.current_floor]
+    fn is_done(&self, floors: u8) -> bool {
+        self.pairs
             .iter()
-            .combinations(2)
-            .chain(self.floors[self.current_floor].iter().map(|d| vec![d]))
-            .cartesian_product(&[Direction::Up, Direction::Down])
-            .map(|(v, d)| Action {
-                holding: v.into_iter().cloned().collect(),
-                direction: *d,
-            })
-            .collect()
+            .all(|p| p.generator == floors - 1 && p.chip == floors - 1)
     }
 
-    fn min_steps(&self) -> Option<u32> {
-        let mut frontier: PriorityQueue<(Self, u32), Reverse<u32>> =
-            PriorityQueue::from_iter([((self.clone(), 0), Reverse(0))]);
-        let mut visited = HashSet::new();
-        loop {
-            let ((current_state, time), _) = frontier.pop()?;
-            if current_state.floors.iter().take(3).all(|v| v.is_empty()) {
-                return Some(time);
+    //(pair index, is generator) for everything on the elevator's floor
+    fn items_here(&self) -> Vec<(usize, bool)> {
+        let mut output = vec![];
+        for (i, p) in self.pairs.iter().enumerate() {
+            if p.generator == self.elevator {
+                output.push((i, true))
             }
-            if !visited.insert(current_state.clone()) {
-                continue;
+            if p.chip == self.elevator {
+                output.push((i, false))
             }
-            current_state
-                .possible_actions()
-                .into_iter()
-                .filter_map(|a| current_state.apply_action(a))
-                .for_each(|s| {
-                    frontier
-                        .push_increase((s.clone(), time + 1), Reverse(time + 1 + s.heuristic()));
-                });
         }
+        output
     }
 
-    fn heuristic(&self) -> u32 {
-        self.floors
-            .iter()
-            .take(3)
-            .enumerate()
-            .map(|(i, v)| v.len() / 2 + v.len() % 2 - (i == self.current_floor) as usize)
-            .sum::<usize>() as u32
+    fn carry(&self, items: &[(usize, bool)], floor: u8) -> Self {
+        let mut output = self.clone();
+        output.elevator = floor;
+        for &(i, is_generator) in items {
+            let pair = &mut output.pairs[i];
+            if is_generator {
+                pair.generator = floor
+            } else {
+                pair.chip = floor
+            }
+        }
+        output
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-enum Direction {
-    Up,
-    Down,
+#[derive(Default, Debug)]
+struct Stats {
+    expanded: usize,
+    generated: usize,
+    invalid: usize,
+    //moving things down when every floor below is empty never helps
+    pruned: usize,
+    //new states that had been seen before exactly as they are
+    revisited: usize,
+    //new states that hadn't, but had with the elements named differently
+    merged: usize,
+    distinct: usize,
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expanded {} states, generated {} moves: {} invalid, {} pruned, {} revisited, \
+             {} merged by symmetry. {} distinct states",
+            self.expanded,
+            self.generated,
+            self.invalid,
+            self.pruned,
+            self.revisited,
+            self.merged,
+            self.distinct
+        )
+    }
 }
 
-#[derive(Debug)]
-struct Action {
-    holding: Vec<Device>,
-    direction: Direction,
+struct Facility {
+    floors: u8,
 }
 
-fn parse_floor(line: &str) -> Vec<Device> {
-    let line = line.split_once("contains").unwrap().1;
-    let line = line.replace("and", ",");
-    line.split(',')
-        .filter(|s| s != &" ")
-        .map(|s| {
-            if s.contains("microchip") {
-                let name = s.split_once(" a ").unwrap().1.split_once('-').unwrap().0;
-                Device {
-                    name: name.to_string(),
-                    device_type: DeviceType::Microchip,
+impl Facility {
+    fn successors(&self, state: &State, stats: &mut Stats) -> Vec<State> {
+        let items = state.items_here();
+        let mut loads: Vec<Vec<(usize, bool)>> = items.iter().map(|&i| vec![i]).collect();
+        for (i, &a) in items.iter().enumerate() {
+            for &b in &items[i + 1..] {
+                loads.push(vec![a, b])
+            }
+        }
+
+        let lowest_used = state
+            .pairs
+            .iter()
+            .map(|p| p.generator.min(p.chip))
+            .min()
+            .unwrap_or(0);
+        let mut output = vec![];
+        for floor in [state.elevator.checked_sub(1), Some(state.elevator + 1)]
+            .into_iter()
+            .flatten()
+            .filter(|&f| f < self.floors)
+        {
+            for load in &loads {
+                stats.generated += 1;
+                if floor < state.elevator && state.elevator <= lowest_used {
+                    stats.pruned += 1;
+                    continue;
                 }
-            } else {
-                let name = s
-                    .split_once(" a ")
-                    .unwrap()
-                    .1
-                    .split_once(" generator")
-                    .unwrap()
-                    .0;
-                Device {
-                    name: name.to_string(),
-                    device_type: DeviceType::Generator,
+                let next = state.carry(load, floor);
+                if !next.is_valid() {
+                    stats.invalid += 1;
+                    continue;
+                }
+                output.push(next)
+            }
+        }
+        output
+    }
+
+    //breadth first over canonical states
+    fn min_steps(&self, start: &State) -> (Option<u32>, Stats) {
+        let mut stats = Stats::default();
+        let mut seen: HashSet<u128> = HashSet::new();
+        let mut frontier = vec![start.clone().canonical()];
+        seen.insert(frontier[0].pack(self.floors));
+        let mut steps = 0;
+        let mut output = None;
+        'search: while !frontier.is_empty() {
+            let mut next_frontier = vec![];
+            for state in frontier {
+                if state.is_done(self.floors) {
+                    output = Some(steps);
+                    break 'search;
+                }
+                stats.expanded += 1;
+                for next in self.successors(&state, &mut stats) {
+                    let named = next.pack(self.floors);
+                    let next = next.canonical();
+                    let packed = next.pack(self.floors);
+                    if seen.insert(packed) {
+                        next_frontier.push(next)
+                    } else if named != packed {
+                        stats.merged += 1
+                    } else {
+                        stats.revisited += 1
+                    }
                 }
             }
+            frontier = next_frontier;
+            steps += 1;
+        }
+
+        stats.distinct = seen.len();
+        (output, stats)
+    }
+}
+
+//one line per floor, from the bottom
+fn parse(input: &str) -> (Facility, State) {
+    let mut generators: HashMap<&str, u8> = HashMap::new();
+    let mut chips: HashMap<&str, u8> = HashMap::new();
+    let lines: Vec<&str> = input.lines().filter(|l| !l.trim().is_empty()).collect();
+    for (floor, line) in lines.iter().enumerate() {
+        let words: Vec<&str> = line
+            .split(|c: char| !c.is_alphanumeric() && c != '-')
+            .filter(|w| !w.is_empty())
+            .collect();
+        for pair in words.windows(2) {
+            if pair[1] == "generator" {
+                generators.insert(pair[0], floor as u8);
+            } else if let Some(name) = pair[0].strip_suffix("-compatible")
+                && pair[1] == "microchip"
+            {
+                chips.insert(name, floor as u8);
+            }
+        }
+    }
+
+    let mut names: Vec<&str> = generators.keys().copied().collect();
+    names.sort();
+    assert_eq!(names.len(), chips.len(), "every chip needs a generator");
+    let pairs = names
+        .into_iter()
+        .map(|name| Pair {
+            generator: generators[name],
+            chip: chips[name],
         })
-        .collect()
+        .collect();
+
+    let floors = lines.len() as u8;
+    let state = State { elevator: 0, pairs };
+    (Facility { floors }, state)
 }
 
 fn solve(input: &str) -> (u32, u32) {
-    let floors: [Vec<Device>; 4] = input
-        .lines()
-        .take(3)
-        .map(parse_floor)
-        .chain([vec![]])
-        .collect_vec()
-        .try_into()
-        .unwrap();
-
-    let mut state = State {
-        floors,
-        current_floor: 0,
+    let (facility, mut state) = parse(input);
+    let (output_1, stats) = facility.min_steps(&state);
+    println!("{stats}");
+
+    let extra = Pair {
+        generator: 0,
+        chip: 0,
     };
+    state.pairs.extend([extra, extra]);
+    let (output_2, stats) = facility.min_steps(&state);
+    println!("{stats}");
 
-    let output_1 = state.min_steps().unwrap();
-
-    state.floors[0].extend([
-        Device {
-            name: "elerium".to_string(),
-            device_type: DeviceType::Generator,
-        },
-        Device {
-            name: "elerium".to_string(),
-            device_type: DeviceType::Microchip,
-        },
-        Device {
-            name: "dilithium".to_string(),
-            device_type: DeviceType::Generator,
-        },
-        Device {
-            name: "dilithium".to_string(),
-            device_type: DeviceType::Microchip,
-        },
-    ]);
-
-    let output_2 = state.min_steps().unwrap();
-
-    (output_1, output_2)
+    (output_1.unwrap(), output_2.unwrap())
 }
 
 fn main() {
@@ -201,3 +246,15 @@ fn main() {
     let (output_1, output_2) = solve(&input);
     println!("part 1: {output_1} part 2: {output_2}")
 }
+
+#[test]
+fn example() {
+    let input = "The first floor contains a hydrogen-compatible microchip and a lithium-compatible microchip.
+The second floor contains a hydrogen generator.
+The third floor contains a lithium generator.
+The fourth floor contains nothing relevant.";
+    let (facility, state) = parse(input);
+    let (steps, stats) = facility.min_steps(&state);
+    assert_eq!(steps, Some(11));
+    assert!(stats.merged > 0);
+}
@@ -1,55 +1,44 @@
-use fancy_regex::Regex;
-use md5::compute;
+use santas_little_helpers::hash_search::{Digest, Window, nibble};
 use std::fs::read_to_string;
 
-fn hash(input: &str, stretch: bool) -> String {
-    if stretch {
-        let mut output = input.to_string();
-        for _ in 0..=2016 {
-            output = format!("{:x}", compute(output));
-        }
-        output
-    } else {
-        format!("{:x}", compute(input))
-    }
+fn triple(digest: &Digest) -> Option<u8> {
+    (0..30)
+        .map(|i| {
+            [
+                nibble(digest, i),
+                nibble(digest, i + 1),
+                nibble(digest, i + 2),
+            ]
+        })
+        .find(|[a, b, c]| a == b && b == c)
+        .map(|[a, _, _]| a)
+}
+
+fn has_quintuple(digest: &Digest, n: u8) -> bool {
+    (0..28).any(|i| (i..i + 5).all(|j| nibble(digest, j) == n))
 }
 
-struct Generator<'a> {
-    salt: &'a str,
-    generated: Vec<String>,
+struct Generator {
+    window: Window,
     index: usize,
 }
 
-impl<'a> Generator<'a> {
-    fn generate_next(&mut self, stretch: bool) {
-        let index = self.generated.len();
-        let s = hash(&format!("{}{}", self.salt, index), stretch);
-        self.generated.push(s);
+impl Generator {
+    fn new(salt: &str, stretch: bool) -> Self {
+        let window = Window::new(salt, if stretch { 2016 } else { 0 });
+        Self { window, index: 0 }
     }
 
-    fn next_key(&mut self, stretch: bool) -> &str {
+    fn next_key(&mut self) -> usize {
         loop {
             self.index += 1;
-            while self.generated.get(self.index).is_none() {
-                self.generate_next(stretch);
-            }
-
-            let candidate = self.generated[self.index].clone();
-            let re = Regex::new(r"(.)\1\1").unwrap();
-            if let Some(c) = re.captures(&candidate).unwrap() {
-                let c = c.get(1).unwrap().as_str();
-                let target = format!("{c}{c}{c}{c}{c}");
-
-                while self.generated.get(self.index + 1001).is_none() {
-                    self.generate_next(stretch);
-                }
-
-                if self.generated[self.index + 1..self.index + 1001]
-                    .iter()
-                    .any(|s| s.contains(&target))
-                {
-                    return &self.generated[self.index];
-                }
+            self.window.advance_to(self.index);
+            let candidate = self.window.get(self.index);
+            if let Some(n) = triple(&candidate)
+                && (self.index + 1..self.index + 1001)
+                    .any(|i| has_quintuple(&self.window.get(i), n))
+            {
+                return self.index;
             }
         }
     }
@@ -57,22 +46,14 @@ impl<'a> Generator<'a> {
 
 fn solve(input: &str) -> (usize, usize) {
     let salt = input.trim();
-    let mut generator = Generator {
-        salt,
-        generated: vec![],
-        index: 0,
-    };
+    let mut generator = Generator::new(salt, false);
     for _ in 0..64 {
-        generator.next_key(false);
+        generator.next_key();
     }
     let output_1 = generator.index;
-    let mut generator = Generator {
-        salt,
-        generated: vec![],
-        index: 0,
-    };
+    let mut generator = Generator::new(salt, true);
     for _ in 0..64 {
-        generator.next_key(true);
+        generator.next_key();
     }
     let output_2 = generator.index;
     (output_1, output_2)
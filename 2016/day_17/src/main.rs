@@ -1,4 +1,4 @@
-use md5::compute;
+use santas_little_helpers::hash_search::{compute, nibble};
 use std::{collections::VecDeque, fs::read_to_string};
 
 #[derive(Clone)]
@@ -10,9 +10,9 @@ struct State<'a> {
 
 impl State<'_> {
     fn possible_next(self) -> Vec<Self> {
-        let hash = format!("{:x}", compute(format!("{}{}", self.salt, self.path)));
-        hash.chars()
-            .take(4)
+        let hash = compute(format!("{}{}", self.salt, self.path));
+        (0..4)
+            .map(|i| nibble(&hash, i))
             .zip([
                 Some(self.position.0).zip(self.position.1.checked_sub(1)),
                 Some(self.position.0).zip(Some(self.position.1 + 1).filter(|&x| x < 4)),
@@ -23,7 +23,7 @@ impl State<'_> {
             ])
             .zip("UDLR".chars())
             .filter_map(|((c, o), d)| Some(c).zip(o).zip(Some(d)))
-            .filter(|((c, _), _)| "bcdef".contains(*c))
+            .filter(|((n, _), _)| *n > 0xa)
             .map(|((_, p), c)| Self {
                 position: p,
                 salt: self.salt,
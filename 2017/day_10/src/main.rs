@@ -1,62 +1,16 @@
+use santas_little_helpers::knot_hash::{hex, knot_hash, sparse_hash};
 use std::fs::read_to_string;
 
-struct State {
-    numbers: Vec<u32>,
-    skip_size: usize,
-    start: usize,
-}
-
-impl State {
-    fn update(&mut self, length: usize) {
-        self.numbers[..length].reverse();
-        let rotate_length = (length + self.skip_size) % 256;
-        self.numbers.rotate_left(rotate_length);
-        self.start += 256 - rotate_length;
-        self.start %= 256;
-        self.skip_size += 1;
-    }
-}
-
-fn knot_hash(input: &str) -> Vec<u8> {
-    let mut state = State {
-        numbers: (0..256).collect(),
-        skip_size: 0,
-        start: 0,
-    };
-    for _ in 0..64 {
-        for length in input.trim().bytes().chain([17, 31, 73, 47, 23]) {
-            state.update(length as usize);
-        }
-    }
-
-    state.numbers.rotate_left(state.start);
-
-    let dense_hash: Vec<u8> = state
-        .numbers
-        .chunks(16)
-        .into_iter()
-        .map(|c| c.iter().fold(0, |acc, x| acc ^ x))
-        .map(|n| n as u8)
-        .collect();
-
-    dense_hash
-}
-
 fn solve(input: &str) -> (u32, String) {
-    let mut state = State {
-        numbers: (0..256).collect(),
-        skip_size: 0,
-        start: 0,
-    };
-    for length in input.trim().split(',').map(|s| s.parse::<usize>().unwrap()) {
-        state.update(length);
-    }
-    state.numbers.rotate_left(state.start);
-    let output_1 = state.numbers[0] * state.numbers[1];
-
-    let hash = knot_hash(input.trim());
+    let lengths: Vec<usize> = input
+        .trim()
+        .split(',')
+        .map(|s| s.parse().unwrap())
+        .collect();
+    let numbers = sparse_hash(&lengths, 1);
+    let output_1 = numbers[0] as u32 * numbers[1] as u32;
 
-    let output_2: String = hash.into_iter().map(|n| format!("{n:02x}")).collect();
+    let output_2 = hex(&knot_hash(input.trim()));
 
     (output_1, output_2)
 }
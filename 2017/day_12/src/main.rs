@@ -1,8 +1,8 @@
-use reunion::{UnionFind, UnionFindTrait};
+use santas_little_helpers::union_find::KeyedUnionFind;
 use std::fs::read_to_string;
 
 fn solve(input: &str) -> (usize, usize) {
-    let mut programs = UnionFind::<u32>::new();
+    let mut programs = KeyedUnionFind::<u32>::new();
     for line in input.lines() {
         let (l, r) = line.split_once("<->").unwrap();
         let id = l.trim().parse().unwrap();
@@ -11,14 +11,8 @@ fn solve(input: &str) -> (usize, usize) {
         }
     }
 
-    let output_1 = programs
-        .subsets()
-        .into_iter()
-        .find(|s| s.contains(&0))
-        .unwrap()
-        .len();
-
-    let output_2 = programs.subsets().len();
+    let output_1 = programs.size(0);
+    let output_2 = programs.num_components();
 
     (output_1, output_2)
 }
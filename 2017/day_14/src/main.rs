@@ -1,90 +1,32 @@
+use santas_little_helpers::{
+    grid::Grid,
+    knot_hash::{bits, knot_hashes},
+    union_find::UnionFind,
+};
 use std::fs::read_to_string;
 
-use reunion::{UnionFind, UnionFindTrait};
-
-struct State {
-    numbers: Vec<u32>,
-    skip_size: usize,
-    start: usize,
-}
-
-impl State {
-    fn update(&mut self, length: usize) {
-        self.numbers[..length].reverse();
-        let rotate_length = (length + self.skip_size) % 256;
-        self.numbers.rotate_left(rotate_length);
-        self.start += 256 - rotate_length;
-        self.start %= 256;
-        self.skip_size += 1;
-    }
-}
-
-fn knot_hash(input: &str) -> Vec<u8> {
-    let mut state = State {
-        numbers: (0..256).collect(),
-        skip_size: 0,
-        start: 0,
-    };
-    for _ in 0..64 {
-        for length in input.trim().bytes().chain([17, 31, 73, 47, 23]) {
-            state.update(length as usize);
-        }
-    }
-
-    state.numbers.rotate_left(state.start);
-
-    let dense_hash: Vec<u8> = state
-        .numbers
-        .chunks(16)
-        .into_iter()
-        .map(|c| c.iter().fold(0, |acc, x| acc ^ x))
-        .map(|n| n as u8)
-        .collect();
-
-    dense_hash
-}
-
-fn bits(n: u8) -> Vec<bool> {
-    (0..8)
-        .map(|n| 1 << n)
-        .map(|x| n & x)
-        .map(|x| x > 0)
-        .rev()
-        .collect()
-}
-
 fn solve(input: &str) -> (u32, usize) {
-    let grid: Vec<Vec<bool>> = (0..128)
+    let keys: Vec<String> = (0..128)
         .map(|i| format!("{}-{}", input.trim(), i))
-        .map(|s| knot_hash(&s))
-        .map(|v| v.into_iter().flat_map(bits).collect())
         .collect();
+    let grid = Grid::from_rows(knot_hashes(&keys).iter().map(bits));
 
-    let output_1 = grid.iter().flat_map(|v| v.iter().map(|&b| b as u32)).sum();
+    let output_1 = grid.cells().iter().map(|&b| b as u32).sum();
 
-    let mut squares = UnionFind::new();
-    for i in 0..128 {
-        for j in 0..128 {
-            if !grid[i][j] {
-                continue;
-            }
-            squares.union((i, j), (i, j));
-            for (i2, j2) in [
-                (i.saturating_sub(1), j),
-                ((i + 1).min(127), j),
-                (i, j.saturating_sub(1)),
-                (i, (j + 1).min(127)),
-            ] {
-                if grid[i2][j2] {
-                    squares.union((i, j), (i2, j2));
-                }
+    //every free square is a component of its own, so they're taken off at the end
+    let mut squares = UnionFind::new(grid.cells().len());
+    for (position, &used) in grid.iter() {
+        if !used {
+            continue;
+        }
+        for neighbour in grid.neighbours(position) {
+            if grid[neighbour] {
+                squares.union(grid.index_of(position), grid.index_of(neighbour));
             }
         }
     }
 
-    println!("{}", squares.size());
-
-    let output_2 = squares.subsets().len();
+    let output_2 = squares.num_components() - (128 * 128 - output_1 as usize);
     (output_1, output_2)
 }
 
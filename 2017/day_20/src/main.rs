@@ -1,10 +1,8 @@
-use std::{
-    collections::HashMap,
-    fs::read_to_string,
-    ops::{Add, AddAssign},
+use santas_little_helpers::{
+    particle::{Particle, survivors},
+    vector::Vector,
 };
-
-use itertools::Itertools;
+use std::fs::read_to_string;
 use winnow::{
     Parser,
     ascii::dec_int,
@@ -12,54 +10,6 @@ use winnow::{
     token::any,
 };
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-struct Vec3d {
-    x: i64,
-    y: i64,
-    z: i64,
-}
-
-impl Add for Vec3d {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        let (x, y, z) = (self.x + rhs.x, self.y + rhs.y, self.z + rhs.z);
-        Self { x, y, z }
-    }
-}
-
-impl AddAssign for Vec3d {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-struct Particle {
-    position: Vec3d,
-    velocity: Vec3d,
-    acceleration: Vec3d,
-}
-
-impl Particle {
-    fn update(&mut self) {
-        self.velocity += self.acceleration;
-        self.position += self.velocity;
-    }
-
-    fn will_never_collide(self, other: Self) -> bool {
-        if (other.position.x - self.position.x) * (other.velocity.x - self.velocity.x) < 0 {
-            return false;
-        }
-
-        if (other.acceleration.x - self.acceleration.x) * (other.velocity.x - self.velocity.x) < 0 {
-            return false;
-        }
-
-        true
-    }
-}
-
 fn all_numbers(mut input: &str) -> Vec<i64> {
     repeat(
         0..,
@@ -69,85 +19,29 @@ fn all_numbers(mut input: &str) -> Vec<i64> {
     .unwrap()
 }
 
-fn solve(input: &str) -> (usize, usize) {
-    let particles: Vec<Vec<i64>> = input.lines().map(|mut l| all_numbers(&mut l)).collect();
-
-    let mut particle_scores: Vec<(usize, (i64, i64, i64))> = particles
-        .iter()
-        .map(|v| {
-            let mut v = v.clone();
-            for i in 0..3 {
-                if v[i + 6] < 0 {
-                    for j in 0..3 {
-                        v[i + 3 * j] *= -1;
-                    }
-                }
+fn parse(input: &str) -> Vec<Particle<3>> {
+    input
+        .lines()
+        .map(|l| {
+            let v = all_numbers(l);
+            Particle {
+                position: Vector::new([v[0], v[1], v[2]]),
+                velocity: Vector::new([v[3], v[4], v[5]]),
+                acceleration: Vector::new([v[6], v[7], v[8]]),
             }
-
-            v
         })
-        .map(|v| (v[6] + v[7] + v[8], v[3] + v[4] + v[5], v[0] + v[1] + v[2]))
-        .enumerate()
-        .collect();
-
-    particle_scores.sort_unstable_by_key(|(_, x)| *x);
-    let output_1 = particle_scores[0].0;
-
-    let mut particles: Vec<Particle> = particles
-        .into_iter()
-        .map(|v| Particle {
-            position: Vec3d {
-                x: v[0],
-                y: v[1],
-                z: v[2],
-            },
-            velocity: Vec3d {
-                x: v[3],
-                y: v[4],
-                z: v[5],
-            },
-            acceleration: Vec3d {
-                x: v[6],
-                y: v[7],
-                z: v[8],
-            },
-        })
-        .collect();
-
-    'outer: loop {
-        for p in particles.iter_mut() {
-            p.update();
-        }
-        particles = particles
-            .into_iter()
-            .fold(
-                HashMap::new(),
-                |mut acc: HashMap<Vec3d, Vec<Particle>>, p: Particle| {
-                    acc.entry(p.position).or_default().push(p);
-                    acc
-                },
-            )
-            .into_values()
-            .filter(|v| v.len() == 1)
-            .flatten()
-            .collect();
-
-        for (p1, p2) in particles
-            .iter()
-            .copied()
-            .permutations(2)
-            .map(|v| (v[0], v[1]))
-        {
-            if !p1.will_never_collide(p2) {
-                continue 'outer;
-            }
-        }
+        .collect()
+}
 
-        break;
-    }
+fn closest_in_long_run(particles: &[Particle<3>]) -> usize {
+    (0..particles.len())
+        .min_by_key(|&i| particles[i].long_run_distance())
+        .unwrap()
+}
 
-    let output_2 = particles.len();
-    (output_1, output_2)
+fn solve(input: &str) -> (usize, usize) {
+    let particles = parse(input);
+    (closest_in_long_run(&particles), survivors(&particles).len())
 }
 
 fn main() {
@@ -155,3 +49,31 @@ fn main() {
     let (output_1, output_2) = solve(&input);
     println!("part 1: {output_1} part 2: {output_2}")
 }
+
+#[test]
+fn examples() {
+    let closest = parse(
+        "p=< 3,0,0>, v=< 2,0,0>, a=<-1,0,0>
+p=< 4,0,0>, v=< 0,0,0>, a=<-2,0,0>",
+    );
+    assert_eq!(closest_in_long_run(&closest), 0);
+    assert_eq!(closest[0].position_at(3), Vector::new([3, 0, 0]));
+    assert_eq!(closest[1].position_at(3), Vector::new([-8, 0, 0]));
+
+    let colliding = parse(
+        "p=<-6,0,0>, v=< 3,0,0>, a=< 0,0,0>
+p=<-4,0,0>, v=< 2,0,0>, a=< 0,0,0>
+p=<-2,0,0>, v=< 1,0,0>, a=< 0,0,0>
+p=< 3,0,0>, v=<-1,0,0>, a=< 0,0,0>",
+    );
+    assert_eq!(colliding[0].collision(colliding[2]), Some(2));
+    assert_eq!(colliding[0].collision(colliding[3]), None);
+    assert_eq!(survivors(&colliding), [3]);
+
+    //a head start in velocity loses out to any more acceleration
+    let slow = parse(
+        "p=<0,0,0>, v=<-100,0,0>, a=<0,0,1>
+p=<0,0,0>, v=<0,0,0>, a=<0,-2,0>",
+    );
+    assert_eq!(closest_in_long_run(&slow), 0);
+}
@@ -0,0 +1,454 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rule {
+    pub write: bool,
+    pub right: bool,
+    pub next: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ParseError {
+    MissingHeader,
+    UnknownState(String),
+    //the line number, from 1, and the line
+    UnexpectedLine(usize, String),
+    IncompleteState(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingHeader => write!(f, "expected the start state and step count first"),
+            Self::UnknownState(name) => write!(f, "no rules for state {name}"),
+            Self::UnexpectedLine(n, line) => write!(f, "line {n}: didn't expect {line:?}"),
+            Self::IncompleteState(name) => {
+                write!(f, "state {name} doesn't say what to do on 0 and 1")
+            }
+        }
+    }
+}
+
+//states are numbered in the order their rules appear, whatever they're called
+#[derive(Clone, Debug)]
+pub struct Blueprint {
+    pub names: Vec<String>,
+    pub rules: Vec<[Rule; 2]>,
+    pub start: usize,
+    pub steps: u64,
+}
+
+//the last word of a sentence, without the full stop
+fn last_word(line: &str) -> &str {
+    line.trim_end_matches(['.', ':'])
+        .rsplit(' ')
+        .next()
+        .unwrap_or("")
+}
+
+impl Blueprint {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let lines: Vec<(usize, &str)> = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty())
+            .collect();
+        let [(_, begin), (_, checksum), body @ ..] = lines.as_slice() else {
+            return Err(ParseError::MissingHeader);
+        };
+        let start = last_word(begin);
+        let steps = checksum
+            .split(' ')
+            .find_map(|w| w.parse().ok())
+            .ok_or(ParseError::MissingHeader)?;
+
+        //first pass names the states, second fills in their rules
+        let mut names: Vec<String> = vec![];
+        for &(_, line) in body {
+            if let Some(name) = line.strip_prefix("In state ") {
+                names.push(name.trim_end_matches(':').to_string())
+            }
+        }
+        let index: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.as_str(), i))
+            .collect();
+        let lookup = |name: &str| {
+            index
+                .get(name)
+                .copied()
+                .ok_or_else(|| ParseError::UnknownState(name.to_string()))
+        };
+
+        let mut partial: Vec<[Option<Rule>; 2]> = vec![[None; 2]; names.len()];
+        let mut state = None;
+        let mut value = None;
+        let mut rule = (None, None);
+        for &(n, line) in body {
+            let unexpected = || ParseError::UnexpectedLine(n, line.to_string());
+            if line.starts_with("In state ") {
+                state = Some(lookup(last_word(line))?);
+            } else if line.starts_with("If the current value is ") {
+                value = Some(match last_word(line) {
+                    "0" => 0,
+                    "1" => 1,
+                    _ => return Err(unexpected()),
+                });
+            } else if line.starts_with("- Write the value ") {
+                rule.0 = Some(last_word(line) == "1");
+            } else if line.starts_with("- Move one slot to the ") {
+                rule.1 = Some(last_word(line) == "right");
+            } else if line.starts_with("- Continue with state ") {
+                let (Some(s), Some(v), (Some(write), Some(right))) = (state, value, rule) else {
+                    return Err(unexpected());
+                };
+                let next = lookup(last_word(line))?;
+                partial[s][v] = Some(Rule { write, right, next });
+                rule = (None, None);
+            } else {
+                return Err(unexpected());
+            }
+        }
+
+        let rules = partial
+            .into_iter()
+            .zip(&names)
+            .map(|(rules, name)| match rules {
+                [Some(a), Some(b)] => Ok([a, b]),
+                _ => Err(ParseError::IncompleteState(name.clone())),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            rules,
+            start: lookup(start)?,
+            steps,
+            names,
+        })
+    }
+}
+
+//grows by doubling on whichever side the head walks off
+#[derive(Clone, Debug)]
+pub struct Tape {
+    cells: Vec<bool>,
+    //the index of slot 0
+    origin: usize,
+    head: usize,
+    ones: usize,
+}
+
+impl Tape {
+    pub fn new() -> Self {
+        Self {
+            cells: vec![false; 64],
+            origin: 32,
+            head: 32,
+            ones: 0,
+        }
+    }
+
+    pub fn position(&self) -> i64 {
+        self.head as i64 - self.origin as i64
+    }
+
+    pub fn ones(&self) -> usize {
+        self.ones
+    }
+
+    pub fn get(&self, slot: i64) -> bool {
+        let i = slot + self.origin as i64;
+        i >= 0 && self.cells.get(i as usize).copied().unwrap_or(false)
+    }
+
+    fn read(&self) -> bool {
+        self.cells[self.head]
+    }
+
+    fn write(&mut self, value: bool) {
+        let cell = &mut self.cells[self.head];
+        if *cell != value {
+            *cell = value;
+            if value {
+                self.ones += 1
+            } else {
+                self.ones -= 1
+            }
+        }
+    }
+
+    fn move_head(&mut self, right: bool) {
+        if right {
+            self.head += 1;
+            if self.head == self.cells.len() {
+                self.cells.resize(2 * self.cells.len(), false)
+            }
+        } else {
+            if self.head == 0 {
+                let added = self.cells.len();
+                let mut cells = vec![false; added];
+                cells.append(&mut self.cells);
+                self.cells = cells;
+                self.origin += added;
+                self.head += added;
+            }
+            self.head -= 1;
+        }
+    }
+
+    //the slots the tape has grown over, which covers every 1
+    fn extent(&self) -> (i64, i64) {
+        let origin = self.origin as i64;
+        (-origin, self.cells.len() as i64 - origin)
+    }
+
+    //like the puzzle: `... 0  1 [0] 1  0 ...`
+    pub fn render(&self, radius: i64) -> String {
+        let position = self.position();
+        let mut output = String::from("...");
+        for slot in position - radius..=position + radius {
+            let c = if self.get(slot) { '1' } else { '0' };
+            if slot == position {
+                output.extend(['[', c, ']'])
+            } else {
+                output.extend([' ', c, ' '])
+            }
+        }
+        output.push_str("...");
+        output
+    }
+}
+
+impl Default for Tape {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//same head position and contents, however much either has grown
+impl PartialEq for Tape {
+    fn eq(&self, other: &Self) -> bool {
+        if self.position() != other.position() || self.ones != other.ones {
+            return false;
+        }
+        let (a, b) = (self.extent(), other.extent());
+        (a.0.min(b.0)..a.1.max(b.1)).all(|slot| self.get(slot) == other.get(slot))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    //steps before the machine first reaches a configuration it comes back to
+    pub start: u64,
+    pub length: u64,
+}
+
+#[derive(Clone)]
+pub struct Machine<'a> {
+    blueprint: &'a Blueprint,
+    state: usize,
+    tape: Tape,
+    steps: u64,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(blueprint: &'a Blueprint) -> Self {
+        Self {
+            blueprint,
+            state: blueprint.start,
+            tape: Tape::new(),
+            steps: 0,
+        }
+    }
+
+    pub fn state(&self) -> &str {
+        &self.blueprint.names[self.state]
+    }
+
+    pub fn tape(&self) -> &Tape {
+        &self.tape
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn checksum(&self) -> usize {
+        self.tape.ones()
+    }
+
+    pub fn step(&mut self) {
+        let rule = self.blueprint.rules[self.state][self.tape.read() as usize];
+        self.tape.write(rule.write);
+        self.tape.move_head(rule.right);
+        self.state = rule.next;
+        self.steps += 1;
+    }
+
+    pub fn run(&mut self, steps: u64) {
+        for _ in 0..steps {
+            self.step()
+        }
+    }
+
+    fn same_configuration(&self, other: &Self) -> bool {
+        self.state == other.state && self.tape == other.tape
+    }
+
+    //brent's algorithm over whole configurations, giving up after `limit` steps.
+    //only exact repeats count, so a machine that keeps writing never repeats
+    pub fn find_cycle(&self, limit: u64) -> Option<Cycle> {
+        let mut power = 1;
+        let mut length = 1;
+        let mut tortoise = self.clone();
+        let mut hare = self.clone();
+        hare.step();
+        while !hare.same_configuration(&tortoise) {
+            if hare.steps - self.steps >= limit {
+                return None;
+            }
+            if power == length {
+                tortoise = hare.clone();
+                power *= 2;
+                length = 0;
+            }
+            hare.step();
+            length += 1;
+        }
+
+        let mut tortoise = self.clone();
+        let mut hare = self.clone();
+        hare.run(length);
+        while !hare.same_configuration(&tortoise) {
+            tortoise.step();
+            hare.step();
+        }
+        Some(Cycle {
+            start: tortoise.steps - self.steps,
+            length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Begin in state A.
+Perform a diagnostic checksum after 6 steps.
+
+In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state B.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state B.
+
+In state B:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state A.
+";
+
+    #[test]
+    fn example() {
+        let blueprint = Blueprint::parse(EXAMPLE).unwrap();
+        let mut machine = Machine::new(&blueprint);
+        machine.run(blueprint.steps);
+        assert_eq!(machine.checksum(), 3);
+        assert_eq!(machine.state(), "A");
+        assert_eq!(machine.tape().render(2), "... 1  1 [0] 1  0 ...");
+        assert_eq!(machine.find_cycle(1000), None);
+    }
+
+    #[test]
+    fn states_by_name() {
+        let renamed = EXAMPLE
+            .replace("state A", "state start")
+            .replace("state B", "state Q");
+        //swap the blocks round so the start state comes second
+        let (header, states) = renamed.split_once("\n\n").unwrap();
+        let (first, second) = states.split_once("\n\n").unwrap();
+        let blueprint = Blueprint::parse(&format!("{header}\n\n{second}\n{first}")).unwrap();
+        assert_eq!(blueprint.names, ["Q", "start"]);
+        let mut machine = Machine::new(&blueprint);
+        machine.run(6);
+        assert_eq!(machine.checksum(), 3);
+
+        let broken = EXAMPLE.replace("Continue with state B", "Continue with state C");
+        assert_eq!(
+            Blueprint::parse(&broken).unwrap_err(),
+            ParseError::UnknownState("C".to_string())
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        //walks right twice writing 1s, then clears them walking back and starts again
+        let blueprint = Blueprint::parse(
+            "\
+Begin in state A.
+Perform a diagnostic checksum after 1 steps.
+In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state B.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state A.
+In state B:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state C.
+  If the current value is 1:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state C.
+In state C:
+  If the current value is 0:
+    - Write the value 0.
+    - Move one slot to the right.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the right.
+    - Continue with state D.
+In state D:
+  If the current value is 0:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state A.
+",
+        )
+        .unwrap();
+        let machine = Machine::new(&blueprint);
+        let cycle = machine.find_cycle(1000).unwrap();
+
+        let mut a = machine.clone();
+        a.run(cycle.start);
+        let mut b = a.clone();
+        b.run(cycle.length);
+        assert!(a.same_configuration(&b));
+        assert_eq!(a.tape().ones(), b.tape().ones());
+    }
+}
@@ -0,0 +1,422 @@
+use santas_little_helpers::grid::Grid;
+use std::fmt::Write;
+
+pub type Position = (usize, usize);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Faction {
+    Elf,
+    Goblin,
+}
+
+impl Faction {
+    const fn symbol(self) -> char {
+        match self {
+            Self::Elf => 'E',
+            Self::Goblin => 'G',
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Settings {
+    pub hp: u32,
+    pub attack: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rules {
+    pub elves: Settings,
+    pub goblins: Settings,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        let settings = Settings { hp: 200, attack: 3 };
+        Self {
+            elves: settings,
+            goblins: settings,
+        }
+    }
+}
+
+impl Rules {
+    const fn settings(&self, faction: Faction) -> Settings {
+        match faction {
+            Faction::Elf => self.elves,
+            Faction::Goblin => self.goblins,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Unit {
+    pub faction: Faction,
+    pub hp: u32,
+    pub position: Position,
+}
+
+//units are referred to by their index in the starting reading order
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    Move {
+        unit: usize,
+        from: Position,
+        to: Position,
+    },
+    Attack {
+        unit: usize,
+        target: usize,
+        damage: u32,
+        hp_left: u32,
+    },
+    Death {
+        unit: usize,
+        position: Position,
+    },
+}
+
+pub struct Round {
+    pub events: Vec<Event>,
+    //false if a unit found no enemies left, ending combat part way through
+    pub complete: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Outcome {
+    pub rounds: u32,
+    pub hp_left: u32,
+    pub winner: Faction,
+}
+
+impl Outcome {
+    pub const fn score(self) -> u32 {
+        self.rounds * self.hp_left
+    }
+}
+
+const fn reading_order((x, y): Position) -> (usize, usize) {
+    (y, x)
+}
+
+pub struct Battle {
+    walls: Grid<bool>,
+    occupied: Grid<Option<usize>>,
+    units: Vec<Unit>,
+    rules: Rules,
+    rounds: u32,
+}
+
+impl Battle {
+    pub fn parse(input: &str, rules: Rules) -> Self {
+        let cells = Grid::parse(input.trim_end(), |c| c);
+        let mut units = vec![];
+        for (position, &c) in cells.iter() {
+            let faction = match c {
+                'E' => Faction::Elf,
+                'G' => Faction::Goblin,
+                '#' | '.' => continue,
+                c => panic!("unrecognized character: {c:?}"),
+            };
+            units.push(Unit {
+                faction,
+                hp: rules.settings(faction).hp,
+                position,
+            });
+        }
+
+        let walls = cells.map(|&c| c == '#');
+        let mut occupied = cells.map(|_| None);
+        for (i, unit) in units.iter().enumerate() {
+            occupied[unit.position] = Some(i)
+        }
+
+        Self {
+            walls,
+            occupied,
+            units,
+            rules,
+            rounds: 0,
+        }
+    }
+
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    pub const fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    pub fn alive(&self, faction: Faction) -> usize {
+        self.units
+            .iter()
+            .filter(|u| u.hp > 0 && u.faction == faction)
+            .count()
+    }
+
+    fn is_open(&self, position: Position) -> bool {
+        !self.walls[position] && self.occupied[position].is_none()
+    }
+
+    //up, left, right, down: the reading order of a square's neighbours
+    fn neighbours(&self, (x, y): Position) -> impl Iterator<Item = Position> + '_ {
+        [
+            y.checked_sub(1).map(|y| (x, y)),
+            x.checked_sub(1).map(|x| (x, y)),
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&p| self.walls.contains(p))
+    }
+
+    fn adjacent_enemy(&self, unit: usize) -> Option<usize> {
+        let Unit {
+            faction, position, ..
+        } = self.units[unit];
+        self.neighbours(position)
+            .filter_map(|p| self.occupied[p])
+            .filter(|&other| self.units[other].faction != faction)
+            .min_by_key(|&other| {
+                let other = self.units[other];
+                (other.hp, reading_order(other.position))
+            })
+    }
+
+    //one breadth first search seeded from every open square next to the unit,
+    //each labelled as its own first step. labels spread layer by layer, keeping
+    //the first in reading order where paths meet, so every square ends up with
+    //its distance and the step to take towards it
+    fn next_step(&self, unit: usize) -> Option<Position> {
+        let Unit {
+            faction, position, ..
+        } = self.units[unit];
+        let in_range = |p: Position| {
+            self.neighbours(p)
+                .any(|q| self.occupied[q].is_some_and(|other| self.units[other].faction != faction))
+        };
+
+        //the first step towards each square and the layer it was reached in
+        let mut first_steps: Grid<Option<(Position, u32)>> = self.walls.map(|_| None);
+        let mut layer: Vec<Position> = self
+            .neighbours(position)
+            .filter(|&p| self.is_open(p))
+            .collect();
+        for &p in &layer {
+            first_steps[p] = Some((p, 1))
+        }
+
+        let mut depth = 1;
+        while !layer.is_empty() {
+            let target = layer
+                .iter()
+                .copied()
+                .filter(|&p| in_range(p))
+                .min_by_key(|&p| reading_order(p));
+            if let Some(target) = target {
+                return first_steps[target].map(|(step, _)| step);
+            }
+
+            depth += 1;
+            let mut next_layer = vec![];
+            for &p in &layer {
+                let (step, _) = first_steps[p].unwrap();
+                for q in self.neighbours(p) {
+                    if q == position || !self.is_open(q) {
+                        continue;
+                    }
+                    match first_steps[q] {
+                        None => {
+                            first_steps[q] = Some((step, depth));
+                            next_layer.push(q)
+                        }
+                        Some((existing, d))
+                            if d == depth && reading_order(step) < reading_order(existing) =>
+                        {
+                            first_steps[q] = Some((step, depth))
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+            layer = next_layer;
+        }
+
+        None
+    }
+
+    fn take_turn(&mut self, unit: usize, events: &mut Vec<Event>) {
+        if self.adjacent_enemy(unit).is_none()
+            && let Some(to) = self.next_step(unit)
+        {
+            let from = self.units[unit].position;
+            self.occupied[from] = None;
+            self.occupied[to] = Some(unit);
+            self.units[unit].position = to;
+            events.push(Event::Move { unit, from, to });
+        }
+
+        let Some(target) = self.adjacent_enemy(unit) else {
+            return;
+        };
+        let damage = self.rules.settings(self.units[unit].faction).attack;
+        let hp_left = self.units[target].hp.saturating_sub(damage);
+        self.units[target].hp = hp_left;
+        events.push(Event::Attack {
+            unit,
+            target,
+            damage,
+            hp_left,
+        });
+        if hp_left == 0 {
+            let position = self.units[target].position;
+            self.occupied[position] = None;
+            events.push(Event::Death {
+                unit: target,
+                position,
+            });
+        }
+    }
+
+    pub fn round(&mut self) -> Round {
+        let mut order: Vec<usize> = (0..self.units.len())
+            .filter(|&i| self.units[i].hp > 0)
+            .collect();
+        order.sort_by_key(|&i| reading_order(self.units[i].position));
+
+        let mut events = vec![];
+        for unit in order {
+            let Unit { faction, hp, .. } = self.units[unit];
+            if hp == 0 {
+                continue;
+            }
+            if !self.units.iter().any(|u| u.hp > 0 && u.faction != faction) {
+                return Round {
+                    events,
+                    complete: false,
+                };
+            }
+            self.take_turn(unit, &mut events);
+        }
+
+        self.rounds += 1;
+        Round {
+            events,
+            complete: true,
+        }
+    }
+
+    //runs to the end, or until `stop` says otherwise after a round
+    pub fn fight(&mut self, mut stop: impl FnMut(&Round) -> bool) -> Option<Outcome> {
+        loop {
+            let round = self.round();
+            if stop(&round) {
+                return None;
+            }
+            if !round.complete {
+                break;
+            }
+        }
+
+        let alive = self.units.iter().filter(|u| u.hp > 0);
+        Some(Outcome {
+            rounds: self.rounds,
+            hp_left: alive.clone().map(|u| u.hp).sum(),
+            winner: alive.map(|u| u.faction).next().unwrap(),
+        })
+    }
+
+    //the map with each row's units and their hp after it, as in the puzzle
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        for y in 0..self.walls.height() {
+            let mut row_units = vec![];
+            for x in 0..self.walls.width() {
+                let c = match self.occupied[(x, y)] {
+                    Some(i) => {
+                        let unit = self.units[i];
+                        row_units.push(format!("{}({})", unit.faction.symbol(), unit.hp));
+                        unit.faction.symbol()
+                    }
+                    None if self.walls[(x, y)] => '#',
+                    None => '.',
+                };
+                output.push(c);
+            }
+            if !row_units.is_empty() {
+                write!(output, "   {}", row_units.join(", ")).unwrap();
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+//the map after `rounds` rounds
+pub fn replay(input: &str, rules: Rules, rounds: u32) -> String {
+    let mut battle = Battle::parse(input, rules);
+    while battle.rounds() < rounds && battle.round().complete {}
+    battle.render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######
+";
+
+    #[test]
+    fn worked_example() {
+        let after_1 = "\
+#######
+#..G..#   G(200)
+#...EG#   E(197), G(197)
+#.#G#G#   G(200), G(197)
+#...#E#   E(197)
+#.....#
+#######
+";
+        assert_eq!(replay(EXAMPLE, Rules::default(), 1), after_1);
+
+        let after_47 = "\
+#######
+#G....#   G(200)
+#.G...#   G(131)
+#.#.#G#   G(59)
+#...#.#
+#....G#   G(200)
+#######
+";
+        assert_eq!(replay(EXAMPLE, Rules::default(), 47), after_47);
+
+        let mut battle = Battle::parse(EXAMPLE, Rules::default());
+        let outcome = battle.fight(|_| false).unwrap();
+        assert_eq!(outcome.score(), 27730);
+        assert_eq!(outcome.winner, Faction::Goblin);
+    }
+
+    #[test]
+    fn stronger_elves() {
+        let rules = Rules {
+            elves: Settings {
+                hp: 200,
+                attack: 15,
+            },
+            ..Rules::default()
+        };
+        let mut battle = Battle::parse(EXAMPLE, rules);
+        let outcome = battle.fight(|_| false).unwrap();
+        assert_eq!(outcome.score(), 4988);
+        assert_eq!(battle.alive(Faction::Elf), 2);
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+pub type Position = (i32, i32);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    const fn from_char(c: char) -> Option<Self> {
+        match c {
+            'N' => Some(Self::Up),
+            'S' => Some(Self::Down),
+            'E' => Some(Self::Right),
+            'W' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    const fn step(self, (x, y): Position) -> Position {
+        match self {
+            Self::Up => (x, y - 1),
+            Self::Down => (x, y + 1),
+            Self::Left => (x - 1, y),
+            Self::Right => (x + 1, y),
+        }
+    }
+}
+
+//rooms are the positions reached by the route, joined by doors. the route
+//starts at (0, 0)
+#[derive(Default)]
+pub struct RoomMap {
+    rooms: HashSet<Position>,
+    //stored with the smaller position first
+    doors: HashSet<(Position, Position)>,
+}
+
+fn door(a: Position, b: Position) -> (Position, Position) {
+    (a.min(b), a.max(b))
+}
+
+impl RoomMap {
+    //follows every branch of the route at once. the positions reached so far are
+    //a set, so branches that meet up again are only followed once from then on.
+    //each open bracket saves where its options start and collects where they end
+    pub fn build(route: &str) -> Self {
+        let route = route.trim();
+        let route = route.strip_prefix('^').unwrap_or(route);
+        let route = route.strip_suffix('$').unwrap_or(route);
+
+        let mut output = Self::default();
+        output.rooms.insert((0, 0));
+        let mut current: HashSet<Position> = HashSet::from([(0, 0)]);
+        let mut stack: Vec<(HashSet<Position>, HashSet<Position>)> = vec![];
+        for c in route.chars() {
+            match c {
+                '(' => stack.push((current.clone(), HashSet::new())),
+                '|' => {
+                    let (starts, ends) = stack.last_mut().expect("| outside of brackets");
+                    ends.extend(current);
+                    current = starts.clone();
+                }
+                ')' => {
+                    let (_, mut ends) = stack.pop().expect("unmatched )");
+                    ends.extend(current);
+                    current = ends;
+                }
+                c => {
+                    let direction = Direction::from_char(c)
+                        .unwrap_or_else(|| panic!("unexpected character {c:?}"));
+                    current = current
+                        .into_iter()
+                        .map(|p| {
+                            let next = direction.step(p);
+                            output.rooms.insert(next);
+                            output.doors.insert(door(p, next));
+                            next
+                        })
+                        .collect();
+                }
+            }
+        }
+        assert!(stack.is_empty(), "unmatched (");
+
+        output
+    }
+
+    pub fn rooms(&self) -> &HashSet<Position> {
+        &self.rooms
+    }
+
+    pub fn has_door(&self, a: Position, b: Position) -> bool {
+        self.doors.contains(&door(a, b))
+    }
+
+    pub fn neighbours(&self, room: Position) -> impl Iterator<Item = Position> + '_ {
+        Direction::ALL
+            .into_iter()
+            .map(move |d| d.step(room))
+            .filter(move |&next| self.has_door(room, next))
+    }
+
+    //the puzzle's drawing: # walls, . rooms, | and - doors and X at the start
+    pub fn render(&self) -> String {
+        let min_x = self.rooms.iter().map(|p| p.0).min().unwrap();
+        let max_x = self.rooms.iter().map(|p| p.0).max().unwrap();
+        let min_y = self.rooms.iter().map(|p| p.1).min().unwrap();
+        let max_y = self.rooms.iter().map(|p| p.1).max().unwrap();
+
+        let mut output = String::new();
+        for y in min_y * 2 - 1..=max_y * 2 + 1 {
+            for x in min_x * 2 - 1..=max_x * 2 + 1 {
+                let c = match (x.rem_euclid(2), y.rem_euclid(2)) {
+                    (0, 0) if (x, y) == (0, 0) => 'X',
+                    (0, 0) if self.rooms.contains(&(x / 2, y / 2)) => '.',
+                    (1, 0) if self.has_door(((x - 1) / 2, y / 2), ((x + 1) / 2, y / 2)) => '|',
+                    (0, 1) if self.has_door((x / 2, (y - 1) / 2), (x / 2, (y + 1) / 2)) => '-',
+                    _ => '#',
+                };
+                output.push(c);
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let map = RoomMap::build("^ENWWW(NEEE|SSE(EE|N))$");
+        let expected = "\
+#########
+#.|.|.|.#
+#-#######
+#.|.|.|.#
+#-#####-#
+#.#.#X|.#
+#-#-#####
+#.|.|.|.#
+#########
+";
+        assert_eq!(map.render(), expected);
+    }
+}
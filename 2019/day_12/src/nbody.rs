@@ -0,0 +1,241 @@
+use santas_little_helpers::{number::lcm_all, vector::Vector};
+use std::fmt::Write;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Body<const N: usize> {
+    pub position: Vector<N>,
+    pub velocity: Vector<N>,
+}
+
+impl<const N: usize> Body<N> {
+    pub fn potential(&self) -> i64 {
+        self.position.manhattan()
+    }
+
+    pub fn kinetic(&self) -> i64 {
+        self.velocity.manhattan()
+    }
+
+    pub fn energy(&self) -> i64 {
+        self.potential() * self.kinetic()
+    }
+
+    //`<x=-1, y=0, z=2>`, standing still. the axes are taken in order whatever
+    //they're called
+    pub fn parse(input: &str) -> Option<Self> {
+        let coordinates = input.trim().strip_prefix('<')?.strip_suffix('>')?;
+        let coordinates: Vec<i64> = coordinates
+            .split(',')
+            .map(|c| c.split_once('=')?.1.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        Some(Self {
+            position: Vector::new(coordinates.try_into().ok()?),
+            velocity: Vector::ZERO,
+        })
+    }
+}
+
+fn axis_name(axis: usize) -> String {
+    ["x", "y", "z", "w"]
+        .get(axis)
+        .map_or_else(|| format!("a{axis}"), |name| name.to_string())
+}
+
+//bodies pulling each other one step closer on every axis
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct System<const N: usize> {
+    pub bodies: Vec<Body<N>>,
+    pub time: i64,
+}
+
+impl<const N: usize> System<N> {
+    pub fn new(positions: impl IntoIterator<Item = Vector<N>>) -> Self {
+        let bodies = positions
+            .into_iter()
+            .map(|position| Body {
+                position,
+                velocity: Vector::ZERO,
+            })
+            .collect();
+        Self { bodies, time: 0 }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        let bodies = input.lines().map(Body::parse).collect::<Option<_>>()?;
+        Some(Self { bodies, time: 0 })
+    }
+
+    //the change in each body's velocity, from where everything is now
+    fn gravity(&self) -> Vec<Vector<N>> {
+        let mut output = vec![Vector::ZERO; self.bodies.len()];
+        for (i, a) in self.bodies.iter().enumerate() {
+            for (j, b) in self.bodies.iter().enumerate().skip(i + 1) {
+                let pull = (b.position - a.position).signum();
+                output[i] += pull;
+                output[j] -= pull
+            }
+        }
+        output
+    }
+
+    pub fn step(&mut self) {
+        let gravity = self.gravity();
+        for (body, pull) in self.bodies.iter_mut().zip(gravity) {
+            body.velocity += pull;
+            body.position += body.velocity
+        }
+        self.time += 1
+    }
+
+    //undoes a step: the positions before it come straight from the velocities,
+    //and the velocities from the gravity between those
+    pub fn step_back(&mut self) {
+        for body in &mut self.bodies {
+            body.position -= body.velocity
+        }
+        let gravity = self.gravity();
+        for (body, pull) in self.bodies.iter_mut().zip(gravity) {
+            body.velocity -= pull
+        }
+        self.time -= 1
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step()
+        }
+    }
+
+    pub fn energy(&self) -> i64 {
+        self.bodies.iter().map(Body::energy).sum()
+    }
+
+    //the total energy now and after each of the next `steps` steps
+    pub fn energies(&self, steps: usize) -> Vec<i64> {
+        let mut current = self.clone();
+        let mut output = vec![current.energy()];
+        for _ in 0..steps {
+            current.step();
+            output.push(current.energy())
+        }
+        output
+    }
+
+    //one axis on its own. the axes never affect each other
+    pub fn project(&self, axis: usize) -> System<1> {
+        let bodies = self
+            .bodies
+            .iter()
+            .map(|b| Body {
+                position: Vector::new([b.position[axis]]),
+                velocity: Vector::new([b.velocity[axis]]),
+            })
+            .collect();
+        System {
+            bodies,
+            time: self.time,
+        }
+    }
+
+    //steps until the axis is back where it is now. every state has exactly one
+    //state before it, so the first one to come round again has to be this one
+    //and there's no need to remember the others
+    pub fn axis_period(&self, axis: usize) -> u64 {
+        let start = self.project(axis);
+        let mut current = start.clone();
+        let mut steps = 0;
+        loop {
+            current.step();
+            steps += 1;
+            if current.bodies == start.bodies {
+                return steps;
+            }
+        }
+    }
+
+    //steps until everything is back where it is now
+    pub fn period(&self) -> u64 {
+        lcm_all((0..N).map(|axis| self.axis_period(axis)))
+    }
+
+    //a row per body per step, for plotting
+    pub fn trajectories_csv(&self, steps: usize) -> String {
+        let mut output = String::from("time,body");
+        for prefix in ["", "v"] {
+            for axis in 0..N {
+                write!(output, ",{prefix}{}", axis_name(axis)).unwrap();
+            }
+        }
+        output.push_str(",energy\n");
+
+        let mut current = self.clone();
+        for step in 0..=steps {
+            if step > 0 {
+                current.step()
+            }
+            for (i, body) in current.bodies.iter().enumerate() {
+                write!(output, "{},{i}", current.time).unwrap();
+                for x in body.position.0.iter().chain(&body.velocity.0) {
+                    write!(output, ",{x}").unwrap();
+                }
+                writeln!(output, ",{}", body.energy()).unwrap();
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "<x=-1, y=0, z=2>
+<x=2, y=-10, z=-7>
+<x=4, y=-8, z=8>
+<x=3, y=5, z=-1>";
+
+    #[test]
+    fn example() {
+        let system = System::<3>::parse(EXAMPLE).unwrap();
+        let energies = system.energies(10);
+        assert_eq!(energies.len(), 11);
+        assert_eq!(energies[10], 179);
+        assert_eq!(
+            (0..3).map(|a| system.axis_period(a)).collect::<Vec<_>>(),
+            [18, 28, 44]
+        );
+        assert_eq!(system.period(), 2772);
+
+        let mut current = system.clone();
+        current.run(2772);
+        assert_eq!(current.bodies, system.bodies);
+        current.run(100);
+        for _ in 0..100 {
+            current.step_back()
+        }
+        assert_eq!(current.bodies, system.bodies);
+        assert_eq!(current.time, 2772);
+
+        let csv = system.trajectories_csv(1);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "time,body,x,y,z,vx,vy,vz,energy");
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[5], "1,0,2,-1,1,3,-1,-1,20");
+        assert_eq!(lines[6], "1,1,3,-7,-4,1,3,3,98");
+    }
+
+    #[test]
+    fn other_shapes() {
+        assert_eq!(System::<3>::parse("<x=1, y=2>"), None);
+        let line = System::<1>::parse("<x=0>\n<x=3>\n<x=5>").unwrap();
+        assert_eq!(line.bodies.len(), 3);
+        assert_eq!(line.period(), line.axis_period(0));
+
+        let plane = System::new([[0, 1], [2, 4], [5, 0], [1, 3], [3, 2]].map(Vector::new));
+        let period = plane.period();
+        assert_eq!(period, 36);
+        let mut current = plane.clone();
+        current.run(period as usize);
+        assert_eq!(current.bodies, plane.bodies);
+    }
+}
@@ -0,0 +1,242 @@
+use santas_little_helpers::grid::Grid;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+type Position = (usize, usize);
+
+const fn bit(c: char) -> u32 {
+    1 << (c.to_ascii_lowercase() as u32 - 'a' as u32)
+}
+
+//one way of walking from a start or key to a key
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Edge {
+    pub to: usize,
+    pub distance: usize,
+    //the keys needed for the doors along the way
+    pub doors: u32,
+    //every key picked up along the way, the one at the end included
+    pub keys: u32,
+}
+
+//the maze squashed down to its starts and keys. nodes are the robots' starts
+//first, then one per letter
+pub struct Graph {
+    pub robots: usize,
+    pub edges: Vec<Vec<Edge>>,
+    pub keys: u32,
+}
+
+impl Graph {
+    pub fn key_node(&self, key: usize) -> usize {
+        self.robots + key
+    }
+
+    //dijkstra over where each robot is and which keys are collected
+    pub fn min_steps(&self) -> Option<usize> {
+        let start: Vec<usize> = (0..self.robots).collect();
+        let mut best: HashMap<(Vec<usize>, u32), usize> = HashMap::from([((start.clone(), 0), 0)]);
+        let mut frontier = BinaryHeap::from([Reverse((0, 0, start))]);
+        while let Some(Reverse((steps, keys, positions))) = frontier.pop() {
+            if keys == self.keys {
+                return Some(steps);
+            }
+            if best[&(positions.clone(), keys)] < steps {
+                continue;
+            }
+            for (robot, &node) in positions.iter().enumerate() {
+                //walking past a key still to be collected is the same as
+                //stopping there first, which is another move anyway
+                for edge in &self.edges[node] {
+                    let target = 1 << edge.to;
+                    if edge.keys & !keys != target || edge.doors & !keys != 0 {
+                        continue;
+                    }
+                    let mut next = positions.clone();
+                    next[robot] = self.key_node(edge.to);
+                    let next_keys = keys | target;
+                    let next_steps = steps + edge.distance;
+                    let entry = best.entry((next.clone(), next_keys)).or_insert(usize::MAX);
+                    if next_steps < *entry {
+                        *entry = next_steps;
+                        frontier.push(Reverse((next_steps, next_keys, next)))
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+pub struct Maze {
+    grid: Grid<char>,
+}
+
+impl Maze {
+    pub fn parse(input: &str) -> Self {
+        Self {
+            grid: Grid::parse(input.trim_end(), |c| c),
+        }
+    }
+
+    pub fn starts(&self) -> Vec<Position> {
+        self.grid
+            .iter()
+            .filter(|&(_, &c)| c == '@')
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    //walls off the middle of each start's 3x3 and puts a robot in every corner
+    pub fn split_vaults(&mut self) {
+        for (x, y) in self.starts() {
+            assert!(
+                (x - 1..=x + 1)
+                    .all(|x| (y - 1..=y + 1).all(|y| matches!(self.grid[(x, y)], '.' | '@'))),
+                "the start at {:?} isn't in open space",
+                (x, y)
+            );
+            for (dx, dy) in [(0, 1), (1, 0), (1, 1), (1, 2), (2, 1)] {
+                self.grid[(x + dx - 1, y + dy - 1)] = '#'
+            }
+            for (dx, dy) in [(0, 0), (0, 2), (2, 0), (2, 2)] {
+                self.grid[(x + dx - 1, y + dy - 1)] = '@'
+            }
+        }
+    }
+
+    //breadth first from `from`, remembering the doors passed and keys picked up.
+    //a square is only walked through again with a set of doors that isn't worse
+    //than before, so a loop gets tried both ways round a door but a tree costs
+    //one walk. the keys don't matter here: a longer way that misses a key the
+    //shorter one passes is never better than stopping for the key
+    fn edges_from(&self, from: Position) -> Vec<Edge> {
+        let mut seen: HashMap<Position, Vec<u32>> = HashMap::new();
+        let mut frontier = VecDeque::from([(from, 0, 0u32, 0u32)]);
+        let mut output: Vec<Edge> = vec![];
+        while let Some((position, distance, mut doors, mut keys)) = frontier.pop_front() {
+            let c = self.grid[position];
+            match c {
+                '#' => continue,
+                'A'..='Z' => doors |= bit(c),
+                'a'..='z' if position != from => {
+                    keys |= bit(c);
+                    let to = bit(c).trailing_zeros() as usize;
+                    //a shorter way with no more doors makes this one pointless
+                    if !output.iter().any(|e| e.to == to && e.doors & !doors == 0) {
+                        output.push(Edge {
+                            to,
+                            distance,
+                            doors,
+                            keys,
+                        })
+                    }
+                }
+                _ => {}
+            }
+
+            let previous = seen.entry(position).or_default();
+            if previous.iter().any(|&d| d & !doors == 0) {
+                continue;
+            }
+            previous.push(doors);
+            for next in self.grid.neighbours(position) {
+                frontier.push_back((next, distance + 1, doors, keys))
+            }
+        }
+        output
+    }
+
+    pub fn graph(&self) -> Graph {
+        let starts = self.starts();
+        let robots = starts.len();
+        let mut edges = vec![vec![]; robots + 26];
+        let mut keys = 0;
+        for (i, &start) in starts.iter().enumerate() {
+            edges[i] = self.edges_from(start)
+        }
+        for (position, &c) in self.grid.iter() {
+            if c.is_ascii_lowercase() {
+                keys |= bit(c);
+                edges[robots + bit(c).trailing_zeros() as usize] = self.edges_from(position)
+            }
+        }
+        Graph {
+            robots,
+            edges,
+            keys,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn min_steps(input: &str) -> Option<usize> {
+        Maze::parse(input).graph().min_steps()
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(
+            min_steps(
+                "\
+########################
+#f.D.E.e.C.b.A.@.a.B.c.#
+######################.#
+#d.....................#
+########################"
+            ),
+            Some(86)
+        );
+        assert_eq!(
+            min_steps(
+                "\
+#################
+#i.G..c...e..H.p#
+########.########
+#j.A..b...f..D.o#
+########@########
+#k.E..a...g..B.n#
+########.########
+#l.F..d...h..C.m#
+#################"
+            ),
+            Some(136)
+        );
+
+        let mut maze = Maze::parse(
+            "\
+#######
+#a.#Cd#
+##...##
+##.@.##
+##...##
+#cB#Ab#
+#######",
+        );
+        maze.split_vaults();
+        assert_eq!(maze.starts().len(), 4);
+        assert_eq!(maze.graph().min_steps(), Some(8));
+    }
+
+    #[test]
+    fn loops() {
+        //straight along the top is shorter but the door needs the key at the end
+        assert_eq!(
+            min_steps(
+                "\
+#######
+#@.A.a#
+#.###.#
+#.....#
+#######"
+            ),
+            Some(8)
+        );
+        assert_eq!(min_steps("#####\n#@Aa#\n#####"), None);
+    }
+}
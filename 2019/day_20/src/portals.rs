@@ -0,0 +1,398 @@
+use santas_little_helpers::grid::Grid;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt::{self, Display},
+};
+
+type Position = (usize, usize);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Inner,
+    Outer,
+}
+
+//one end of a portal: the open square next to its label
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Portal {
+    pub label: String,
+    pub side: Side,
+    pub position: Position,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    //portals just teleport
+    Flat,
+    //inner portals go a level down and outer ones back up. outer portals are
+    //walls on the top level
+    Recursive,
+    //recursive, but never deeper than this
+    Limited(usize),
+}
+
+impl Mode {
+    //the level after walking through `side`, if that's allowed
+    fn through(self, side: Side, level: usize) -> Option<usize> {
+        match (self, side) {
+            (Self::Flat, _) => Some(level),
+            (Self::Limited(limit), Side::Inner) if level >= limit => None,
+            (_, Side::Inner) => Some(level + 1),
+            (_, Side::Outer) => level.checked_sub(1),
+        }
+    }
+}
+
+//standing at a portal on a level. 0 is the outermost
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Hop {
+    pub portal: usize,
+    pub level: usize,
+}
+
+pub struct Route {
+    pub steps: usize,
+    pub hops: Vec<Hop>,
+}
+
+pub struct Maze {
+    pub portals: Vec<Portal>,
+    //walking distances between portals on the same level
+    edges: Vec<Vec<(usize, usize)>>,
+    //the other end of each portal
+    partners: Vec<Option<usize>>,
+}
+
+impl Maze {
+    pub fn parse(input: &str) -> Self {
+        //editors like to strip the trailing spaces
+        let width = input.lines().map(|l| l.len()).max().unwrap_or(0);
+        let grid = Grid::from_rows(input.lines().map(|l| {
+            l.chars()
+                .chain(std::iter::repeat(' '))
+                .take(width)
+                .collect::<Vec<_>>()
+        }));
+
+        let mut portals = vec![];
+        for (position, &c) in grid.iter() {
+            if c != '.' {
+                continue;
+            }
+            let (x, y) = position;
+            //two squares out in each direction, with the letters read left to
+            //right or top to bottom
+            let label = [(-1, 0), (1, 0), (0, -1), (0, 1)].into_iter().find_map(
+                |(dx, dy): (isize, isize)| {
+                    let near = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+                    let far = (
+                        near.0.checked_add_signed(dx)?,
+                        near.1.checked_add_signed(dy)?,
+                    );
+                    let (a, b) = (*grid.get(near)?, *grid.get(far)?);
+                    if !a.is_ascii_uppercase() || !b.is_ascii_uppercase() {
+                        return None;
+                    }
+                    let label = if dx + dy < 0 { [b, a] } else { [a, b] };
+                    Some((label.iter().collect::<String>(), far))
+                },
+            );
+            let Some((label, far)) = label else {
+                continue;
+            };
+            let on_edge =
+                far.0 == 0 || far.1 == 0 || far.0 == width - 1 || far.1 == grid.height() - 1;
+            portals.push(Portal {
+                label,
+                side: if on_edge { Side::Outer } else { Side::Inner },
+                position,
+            });
+        }
+
+        let partners = portals
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                portals
+                    .iter()
+                    .enumerate()
+                    .position(|(j, q)| i != j && p.label == q.label)
+            })
+            .collect();
+        let at: HashMap<Position, usize> = portals
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.position, i))
+            .collect();
+        let edges = portals
+            .iter()
+            .map(|p| {
+                //breadth first over the open squares to every other portal
+                let mut distances = HashMap::from([(p.position, 0)]);
+                let mut frontier = VecDeque::from([p.position]);
+                let mut output = vec![];
+                while let Some(current) = frontier.pop_front() {
+                    let distance = distances[&current];
+                    if let Some(&j) = at.get(&current)
+                        && current != p.position
+                    {
+                        output.push((j, distance))
+                    }
+                    for next in grid.neighbours(current) {
+                        if grid[next] == '.' && !distances.contains_key(&next) {
+                            distances.insert(next, distance + 1);
+                            frontier.push_back(next)
+                        }
+                    }
+                }
+                output
+            })
+            .collect();
+
+        Self {
+            portals,
+            edges,
+            partners,
+        }
+    }
+
+    pub fn find(&self, label: &str) -> Option<usize> {
+        self.portals.iter().position(|p| p.label == label)
+    }
+
+    //the shortest walk between each pair of portals on one level, going as deep
+    //as it likes in between but never further out. every level looks the same
+    //from below, so it doesn't matter which. each pass lets the walks go one
+    //more level down using the last pass's walks there. walks only ever get
+    //shorter or appear, so the passes have to settle, and once one changes
+    //nothing no deeper detour can help either
+    fn walks(&self) -> Vec<Vec<Option<usize>>> {
+        let n = self.portals.len();
+        let mut walks = vec![vec![None; n]; n];
+        loop {
+            let mut next = vec![vec![None; n]; n];
+            for (i, row) in next.iter_mut().enumerate() {
+                row[i] = Some(0);
+                for &(j, distance) in &self.edges[i] {
+                    relax(&mut row[j], distance)
+                }
+                //down through an inner portal, around the level below and back
+                //up through one of its outer portals
+                let Some(below) = self.partners[i].filter(|_| self.portals[i].side == Side::Inner)
+                else {
+                    continue;
+                };
+                for (j, &walk) in walks[below].iter().enumerate() {
+                    let (Some(walk), Some(up)) = (walk, self.partners[j]) else {
+                        continue;
+                    };
+                    if self.portals[j].side == Side::Outer {
+                        relax(&mut row[up], walk + 2)
+                    }
+                }
+            }
+            //floyd-warshall over those
+            for k in 0..n {
+                for i in 0..n {
+                    for j in 0..n {
+                        if let (Some(a), Some(b)) = (next[i][k], next[k][j]) {
+                            relax(&mut next[i][j], a + b)
+                        }
+                    }
+                }
+            }
+            if next == walks {
+                return walks;
+            }
+            walks = next
+        }
+    }
+
+    //dijkstra over (portal, level), from AA to ZZ on the top level. recursive
+    //mode already knows how long the route is from the walks, so it only has to
+    //find how deep it goes
+    pub fn shortest_route(&self, mode: Mode) -> Option<Route> {
+        if mode == Mode::Recursive {
+            let steps = self.walks()[self.find("AA")?][self.find("ZZ")?]?;
+            return (0..)
+                .filter_map(|limit| self.shortest_route(Mode::Limited(limit)))
+                .find(|route| route.steps == steps);
+        }
+        let start = Hop {
+            portal: self.find("AA")?,
+            level: 0,
+        };
+        let goal = Hop {
+            portal: self.find("ZZ")?,
+            level: 0,
+        };
+
+        let mut best: HashMap<Hop, (usize, Option<Hop>)> = HashMap::from([(start, (0, None))]);
+        let mut frontier = BinaryHeap::from([Reverse((0, start.portal, start.level))]);
+        while let Some(Reverse((steps, portal, level))) = frontier.pop() {
+            let hop = Hop { portal, level };
+            if hop == goal {
+                break;
+            }
+            if best[&hop].0 < steps {
+                continue;
+            }
+
+            let walks = self.edges[portal]
+                .iter()
+                .map(|&(to, distance)| (Hop { portal: to, level }, distance));
+            let warp = self.partners[portal].and_then(|to| {
+                let level = mode.through(self.portals[portal].side, level)?;
+                Some((Hop { portal: to, level }, 1))
+            });
+            for (next, distance) in walks.chain(warp) {
+                let entry = best.entry(next).or_insert((usize::MAX, None));
+                if steps + distance < entry.0 {
+                    *entry = (steps + distance, Some(hop));
+                    frontier.push(Reverse((steps + distance, next.portal, next.level)))
+                }
+            }
+        }
+
+        let &(steps, _) = best.get(&goal)?;
+        let mut hops = vec![goal];
+        while let Some(&(_, Some(previous))) = best.get(hops.last().unwrap()) {
+            hops.push(previous)
+        }
+        hops.reverse();
+        Some(Route { steps, hops })
+    }
+
+    pub fn describe(&self, route: &Route) -> String {
+        route
+            .hops
+            .iter()
+            .map(|h| format!("{}({})", self.portals[h.portal], h.level))
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+}
+
+fn relax(best: &mut Option<usize>, distance: usize) {
+    if best.is_none_or(|b| distance < b) {
+        *best = Some(distance)
+    }
+}
+
+impl Display for Portal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = match self.side {
+            Side::Inner => "in",
+            Side::Outer => "out",
+        };
+        write!(f, "{} {side}", self.label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "         A
+         A
+  #######.#########
+  #######.........#
+  #######.#######.#
+  #######.#######.#
+  #######.#######.#
+  #####  B    ###.#
+BC...##  C    ###.#
+  ##.##       ###.#
+  ##...DE  F  ###.#
+  #####    G  ###.#
+  #########.#####.#
+DE..#######...###.#
+  #.#########.###.#
+FG..#########.....#
+  ###########.#####
+             Z
+             Z
+";
+
+    const RECURSIVE_EXAMPLE: &str = "             Z L X W       C
+             Z P Q B       K
+  ###########.#.#.#.#######.###############
+  #...#.......#.#.......#.#.......#.#.#...#
+  ###.#.#.#.#.#.#.#.###.#.#.#######.#.#.###
+  #.#...#.#.#...#.#.#...#...#...#.#.......#
+  #.###.#######.###.###.#.###.###.#.#######
+  #...#.......#.#...#...#.............#...#
+  #.#########.#######.#.#######.#######.###
+  #...#.#    F       R I       Z    #.#.#.#
+  #.###.#    D       E C       H    #.#.#.#
+  #.#...#                           #...#.#
+  #.###.#                           #.###.#
+  #.#....OA                       WB..#.#..ZH
+  #.###.#                           #.#.#.#
+CJ......#                           #.....#
+  #######                           #######
+  #.#....CK                         #......IC
+  #.###.#                           #.###.#
+  #.....#                           #...#.#
+  ###.###                           #.#.#.#
+XF....#.#                         RF..#.#.#
+  #####.#                           #######
+  #......CJ                       NM..#...#
+  ###.#.#                           #.###.#
+RE....#.#                           #......RF
+  ###.###        X   X       L      #.#.#.#
+  #.....#        F   Q       P      #.#.#.#
+  ###.###########.###.#######.#########.###
+  #.....#...#.....#.......#...#.....#.#...#
+  #####.#.###.#######.#######.###.###.#.#.#
+  #.......#.......#.#.#.#.#...#...#...#.#.#
+  #####.###.#####.#.#.#.#.###.###.#.###.###
+  #.......#.....#.#...#...............#...#
+  #############.#.#.###.###################
+               A O F   N
+               A A D   M
+";
+
+    #[test]
+    fn example() {
+        let maze = Maze::parse(EXAMPLE);
+        assert_eq!(maze.portals.len(), 8);
+        let bc = maze.portals.iter().filter(|p| p.label == "BC");
+        assert_eq!(
+            bc.map(|p| p.side).collect::<Vec<_>>(),
+            [Side::Inner, Side::Outer]
+        );
+
+        let route = maze.shortest_route(Mode::Flat).unwrap();
+        assert_eq!(route.steps, 23);
+        assert_eq!(
+            maze.describe(&route),
+            "AA out(0) -> BC in(0) -> BC out(0) -> DE in(0) -> DE out(0) -> \
+             FG out(0) -> FG in(0) -> ZZ out(0)"
+        );
+
+        //no portal helps once levels count, so it's the long way round
+        let route = maze.shortest_route(Mode::Recursive).unwrap();
+        assert_eq!(route.steps, 26);
+        assert_eq!(route.hops.len(), 2);
+        assert_eq!(maze.shortest_route(Mode::Limited(0)).unwrap().steps, 26);
+
+        //without the long way round, ZZ is only ever reached a level down
+        let blocked = Maze::parse(&EXAMPLE.replacen("#######.#######.#", "#######.#########", 1));
+        assert_eq!(blocked.shortest_route(Mode::Flat).unwrap().steps, 23);
+        assert!(blocked.shortest_route(Mode::Recursive).is_none());
+    }
+
+    #[test]
+    fn recursive_example() {
+        let maze = Maze::parse(RECURSIVE_EXAMPLE);
+        let route = maze.shortest_route(Mode::Recursive).unwrap();
+        assert_eq!(route.steps, 396);
+        let deepest = route.hops.iter().map(|h| h.level).max();
+        assert_eq!(deepest, Some(10));
+
+        assert!(maze.shortest_route(Mode::Limited(9)).is_none());
+        assert_eq!(maze.shortest_route(Mode::Limited(10)).unwrap().steps, 396);
+    }
+}
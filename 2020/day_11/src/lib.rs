@@ -1,267 +1,101 @@
-use std::fmt::Display;
+use santas_little_helpers::automaton::{Automaton, Graph, Rule};
+use santas_little_helpers::grid::Grid;
 use std::fs::read_to_string;
-use std::iter::successors;
-use std::ops::{Add, Neg, Sub};
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
-struct Position {
-    x: i32,
-    y: i32,
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+//only seats take part, numbered in reading order, each linked to the seats it
+//pays attention to. floor never changes so it isn't a cell at all
+struct Seats {
+    grid: Grid<Option<usize>>,
+    occupied: Vec<usize>,
 }
 
-impl Position {
-    fn step(self, direction: Direction) -> Self {
-        self + direction.into()
-    }
-
-    fn surrounding(self) -> [Self; 8] {
-        [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ]
-        .map(|(x, y)| Self { x, y })
-        .map(|p| self + p)
-    }
-}
-
-impl From<Direction> for Position {
-    fn from(value: Direction) -> Self {
-        let (x, y) = match value {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-            Direction::UpLeft => (-1, -1),
-            Direction::UpRight => (1, -1),
-            Direction::DownLeft => (-1, 1),
-            Direction::DownRight => (1, 1),
-        };
-
-        Self { x, y }
-    }
-}
-
-impl Add for Position {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        let (x, y) = (self.x + rhs.x, self.y + rhs.y);
-        Self { x, y }
-    }
-}
-
-impl Neg for Position {
-    type Output = Self;
-    fn neg(self) -> Self::Output {
-        let (x, y) = (-self.x, -self.y);
-        Self { x, y }
-    }
-}
-
-impl Sub for Position {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self::Output {
-        self + -rhs
-    }
-}
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-    UpLeft,
-    UpRight,
-    DownLeft,
-    DownRight,
-}
-
-impl Direction {
-    const fn all() -> [Self; 8] {
-        [
-            Self::Up,
-            Self::Down,
-            Self::Left,
-            Self::Right,
-            Self::UpRight,
-            Self::UpLeft,
-            Self::DownRight,
-            Self::DownLeft,
-        ]
-    }
-}
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Tile {
-    Occupied,
-    Vacant,
-    Floor,
-}
-
-#[derive(Clone)]
-struct Grid(Vec<Vec<Tile>>);
-
-impl Grid {
+impl Seats {
     fn new(input: &str) -> Self {
-        Self(
-            input
-                .lines()
-                .map(|l| {
-                    l.chars()
-                        .map(|c| match c {
-                            'L' => Tile::Vacant,
-                            '#' => Tile::Occupied,
-                            '.' => Tile::Floor,
-                            _ => panic!("unrecognized character: {c}"),
-                        })
-                        .collect()
-                })
-                .collect(),
-        )
-    }
-
-    fn get(&self, position: Position) -> Option<Tile> {
-        let x: usize = position.x.try_into().ok()?;
-        let y: usize = position.y.try_into().ok()?;
-        self.0.get(y)?.get(x).copied()
-    }
-
-    fn count_occupied_surrounding(&self, position: Position) -> usize {
-        position
-            .surrounding()
-            .into_iter()
-            .flat_map(|p| self.get(p))
-            .filter(|t| *t == Tile::Occupied)
-            .count()
-    }
-
-    fn step(&mut self) -> bool {
-        let mut grid = self.0.clone();
-        let mut changed = false;
-        grid.iter_mut()
-            .enumerate()
-            .flat_map(|(y, r)| r.iter_mut().enumerate().map(move |(x, t)| (x, y, t)))
-            .map(|(x, y, t)| (x as i32, y as i32, t))
-            .map(|(x, y, t)| (Position { x, y }, t))
-            .for_each(|(p, t)| {
-                let surrounding = self.count_occupied_surrounding(p);
-
-                match *t {
-                    Tile::Floor => {}
-                    Tile::Vacant => {
-                        if surrounding == 0 {
-                            *t = Tile::Occupied;
-                            changed = true;
-                        }
-                    }
-                    Tile::Occupied => {
-                        if surrounding >= 4 {
-                            *t = Tile::Vacant;
-                            changed = true;
-                        }
-                    }
+        let chars = Grid::parse(input.trim_end(), |c| c);
+        let mut count = 0;
+        let mut occupied = vec![];
+        let grid = chars.map(|&c| match c {
+            'L' | '#' => {
+                if c == '#' {
+                    occupied.push(count)
                 }
-            });
-        self.0 = grid;
-        changed
-    }
-
-    fn scan(&self, position: Position, direction: Direction) -> Vec<Tile> {
-        successors(Some(position.step(direction)), |p| Some(p.step(direction)))
-            .map(|p| self.get(p))
-            .take_while(|o| o.is_some())
-            .flatten()
-            .collect()
-    }
-
-    fn get_far(&self, position: Position, direction: Direction) -> Option<Tile> {
-        self.scan(position, direction)
-            .into_iter()
-            .find(|t| *t != Tile::Floor)
-    }
-
-    fn count_occupied_surrounding_far(&self, position: Position) -> usize {
-        Direction::all()
-            .into_iter()
-            .filter_map(|d| self.get_far(position, d))
-            .filter(|t| *t == Tile::Occupied)
-            .count()
-    }
-
-    fn step_part_2(&mut self) -> bool {
-        let mut grid = self.0.clone();
-        let mut changed = false;
-        grid.iter_mut()
-            .enumerate()
-            .flat_map(|(y, r)| r.iter_mut().enumerate().map(move |(x, t)| (x, y, t)))
-            .map(|(x, y, t)| (x as i32, y as i32, t))
-            .map(|(x, y, t)| (Position { x, y }, t))
-            .for_each(|(p, t)| {
-                let surrounding = self.count_occupied_surrounding_far(p);
-
-                match *t {
-                    Tile::Floor => {}
-                    Tile::Vacant => {
-                        if surrounding == 0 {
-                            *t = Tile::Occupied;
-                            changed = true;
-                        }
-                    }
-                    Tile::Occupied => {
-                        if surrounding >= 5 {
-                            *t = Tile::Vacant;
-                            changed = true;
-                        }
+                count += 1;
+                Some(count - 1)
+            }
+            '.' => None,
+            _ => panic!("unrecognized character: {c}"),
+        });
+        Self { grid, occupied }
+    }
+
+    //the first seat in each direction, looking no further than `range` squares
+    fn topology(&self, range: usize) -> Graph {
+        let mut neighbours = vec![];
+        for ((x, y), seat) in self.grid.iter() {
+            if seat.is_none() {
+                continue;
+            }
+            let seen = DIRECTIONS.iter().filter_map(|&(dx, dy)| {
+                (1..=range).find_map(|i| {
+                    let p = x
+                        .checked_add_signed(dx * i as isize)
+                        .zip(y.checked_add_signed(dy * i as isize));
+                    //off the edge ends the search, floor carries on
+                    match p.and_then(|p| self.grid.get(p)) {
+                        None => Some(None),
+                        Some(&seat) => seat.map(Some),
                     }
-                }
+                })?
             });
-
-        self.0 = grid;
-        changed
-    }
-
-    fn count_occupied(&self) -> usize {
-        self.0
-            .iter()
-            .flatten()
-            .filter(|t| **t == Tile::Occupied)
-            .count()
-    }
-}
-
-impl Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for r in &self.0 {
-            for t in r {
-                let output = match t {
-                    Tile::Occupied => '#',
-                    Tile::Vacant => 'L',
-                    Tile::Floor => '.',
-                };
-                write!(f, "{output}")?
-            }
-            writeln!(f)?
+            neighbours.push(seen.collect())
         }
+        Graph(neighbours)
+    }
 
-        Ok(())
+    //people sit down with nobody around and get up at `tolerance` neighbours
+    fn settle(&self, range: usize, tolerance: usize) -> usize {
+        let survival: Vec<usize> = (0..tolerance).collect();
+        let rule = Rule::new(&[0], &survival);
+        let mut area = Automaton::new(self.topology(range), rule, self.occupied.clone());
+        area.run_until_stable();
+        area.population()
     }
 }
 
 pub fn solve(path: &str) -> (usize, usize) {
-    println!("{path}");
     let input = read_to_string(path).unwrap();
-    let mut grid = Grid::new(&input);
-    let mut grid_2 = grid.clone();
-    while grid.step() {}
-    grid.count_occupied();
-    while grid_2.step_part_2() {}
-    let output_1 = grid.count_occupied();
-    let output_2 = grid_2.count_occupied();
+    let seats = Seats::new(&input);
+    let output_1 = seats.settle(1, 4);
+    let output_2 = seats.settle(usize::MAX, 5);
     (output_1, output_2)
 }
+
+#[test]
+fn example() {
+    let seats = Seats::new(
+        "L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+",
+    );
+    assert_eq!(seats.settle(1, 4), 37);
+    assert_eq!(seats.settle(usize::MAX, 5), 26);
+}
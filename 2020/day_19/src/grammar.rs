@@ -0,0 +1,302 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+
+pub type RuleId = usize;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Symbol {
+    Rule(RuleId),
+    Char(char),
+}
+
+//each rule is a list of alternatives, each a sequence of symbols
+pub struct Grammar {
+    rules: HashMap<RuleId, Vec<Vec<Symbol>>>,
+}
+
+fn parse_rule(line: &str) -> (RuleId, Vec<Vec<Symbol>>) {
+    let (id, body) = line.split_once(": ").unwrap();
+    let alternatives = body
+        .split(" | ")
+        .map(|alternative| {
+            let symbols: Vec<Symbol> = alternative
+                .split_whitespace()
+                .flat_map(|word| match word.strip_prefix('"') {
+                    Some(s) => s.trim_end_matches('"').chars().map(Symbol::Char).collect(),
+                    None => vec![Symbol::Rule(word.parse().unwrap())],
+                })
+                .collect();
+            assert!(!symbols.is_empty(), "empty alternative in rule {id}");
+            symbols
+        })
+        .collect();
+
+    (id.parse().unwrap(), alternatives)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Item {
+    rule: RuleId,
+    alternative: usize,
+    dot: usize,
+    origin: usize,
+}
+
+//every (rule, start, end) such that the rule derives message[start..end],
+//for the spans the parser had reason to look at
+struct Chart {
+    completed: HashSet<(RuleId, usize, usize)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Tree {
+    Leaf(char),
+    Node {
+        rule: RuleId,
+        text: String,
+        children: Vec<Tree>,
+    },
+}
+
+impl Tree {
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        match self {
+            Tree::Leaf(c) => writeln!(f, "{indent}{c:?}"),
+            Tree::Node {
+                rule,
+                text,
+                children,
+            } => {
+                writeln!(f, "{indent}{rule}: {text:?}")?;
+                for child in children {
+                    child.write(f, depth + 1)?
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl Grammar {
+    pub fn parse(input: &str) -> Self {
+        let rules: HashMap<RuleId, Vec<Vec<Symbol>>> = input.lines().map(parse_rule).collect();
+        let output = Self { rules };
+        output.check();
+        output
+    }
+
+    fn check(&self) {
+        for symbol in self.rules.values().flatten().flatten() {
+            if let Symbol::Rule(id) = symbol {
+                assert!(self.rules.contains_key(id), "rule {id} is not defined");
+            }
+        }
+    }
+
+    //replaces (or adds) a rule, given in the same format as the input
+    pub fn set_rule(&mut self, line: &str) {
+        let (id, alternatives) = parse_rule(line);
+        self.rules.insert(id, alternatives);
+        self.check();
+    }
+
+    //earley parsing. no alternative is empty, so every completed item started
+    //at an earlier position and its parents are already all known
+    fn chart(&self, message: &[char], starts: impl IntoIterator<Item = RuleId>) -> Chart {
+        fn add(sets: &mut [Vec<Item>], seen: &mut [HashSet<Item>], k: usize, item: Item) {
+            if seen[k].insert(item) {
+                sets[k].push(item)
+            }
+        }
+
+        let n = message.len();
+        let mut sets: Vec<Vec<Item>> = vec![vec![]; n + 1];
+        let mut seen: Vec<HashSet<Item>> = vec![HashSet::new(); n + 1];
+        let mut completed = HashSet::new();
+        let predict = |sets: &mut [Vec<Item>], seen: &mut [HashSet<Item>], rule, k| {
+            for alternative in 0..self.rules[&rule].len() {
+                let item = Item {
+                    rule,
+                    alternative,
+                    dot: 0,
+                    origin: k,
+                };
+                add(sets, seen, k, item)
+            }
+        };
+
+        for rule in starts {
+            predict(&mut sets, &mut seen, rule, 0)
+        }
+
+        for k in 0..=n {
+            let mut i = 0;
+            while let Some(&item) = sets[k].get(i) {
+                i += 1;
+                let advanced = Item {
+                    dot: item.dot + 1,
+                    ..item
+                };
+                match self.rules[&item.rule][item.alternative].get(item.dot) {
+                    None => {
+                        completed.insert((item.rule, item.origin, k));
+                        let mut j = 0;
+                        while let Some(&parent) = sets[item.origin].get(j) {
+                            j += 1;
+                            let alternative = &self.rules[&parent.rule][parent.alternative];
+                            if alternative.get(parent.dot) == Some(&Symbol::Rule(item.rule)) {
+                                let parent = Item {
+                                    dot: parent.dot + 1,
+                                    ..parent
+                                };
+                                add(&mut sets, &mut seen, k, parent)
+                            }
+                        }
+                    }
+                    Some(Symbol::Char(c)) => {
+                        if message.get(k) == Some(c) {
+                            add(&mut sets, &mut seen, k + 1, advanced)
+                        }
+                    }
+                    Some(&Symbol::Rule(rule)) => predict(&mut sets, &mut seen, rule, k),
+                }
+            }
+        }
+
+        Chart { completed }
+    }
+
+    pub fn matches(&self, rule: RuleId, message: &str) -> bool {
+        let message: Vec<char> = message.chars().collect();
+        let chart = self.chart(&message, [rule]);
+        chart.completed.contains(&(rule, 0, message.len()))
+    }
+
+    //every rule that matches the whole message
+    pub fn matching_rules(&self, message: &str) -> Vec<RuleId> {
+        let message: Vec<char> = message.chars().collect();
+        let chart = self.chart(&message, self.rules.keys().copied());
+        let mut output: Vec<RuleId> = self
+            .rules
+            .keys()
+            .copied()
+            .filter(|&rule| chart.completed.contains(&(rule, 0, message.len())))
+            .collect();
+        output.sort();
+        output
+    }
+
+    pub fn parse_tree(&self, rule: RuleId, message: &str) -> Option<Tree> {
+        let message: Vec<char> = message.chars().collect();
+        let chart = self.chart(&message, [rule]);
+        let builder = TreeBuilder {
+            grammar: self,
+            chart: &chart,
+            message: &message,
+        };
+        builder.node(rule, 0, message.len(), &mut vec![])
+    }
+}
+
+struct TreeBuilder<'a> {
+    grammar: &'a Grammar,
+    chart: &'a Chart,
+    message: &'a [char],
+}
+
+impl TreeBuilder<'_> {
+    //`path` holds the spans being built above this one, so unit rule cycles
+    //like `8: 8 | 42` can't send it round forever
+    fn node(
+        &self,
+        rule: RuleId,
+        start: usize,
+        end: usize,
+        path: &mut Vec<(RuleId, usize, usize)>,
+    ) -> Option<Tree> {
+        let span = (rule, start, end);
+        if !self.chart.completed.contains(&span) || path.contains(&span) {
+            return None;
+        }
+
+        path.push(span);
+        let children = self.grammar.rules[&rule]
+            .iter()
+            .find_map(|alternative| self.sequence(alternative, start, end, path));
+        path.pop();
+
+        Some(Tree::Node {
+            rule,
+            text: self.message[start..end].iter().collect(),
+            children: children?,
+        })
+    }
+
+    fn sequence(
+        &self,
+        symbols: &[Symbol],
+        start: usize,
+        end: usize,
+        path: &mut Vec<(RuleId, usize, usize)>,
+    ) -> Option<Vec<Tree>> {
+        let Some((&first, rest)) = symbols.split_first() else {
+            return (start == end).then(Vec::new);
+        };
+
+        let ends = if rest.is_empty() {
+            end..=end
+        } else {
+            start + 1..=end
+        };
+        ends.rev().find_map(|mid| {
+            let tree = match first {
+                Symbol::Char(c) => {
+                    if mid != start + 1 || self.message.get(start) != Some(&c) {
+                        return None;
+                    }
+                    Tree::Leaf(c)
+                }
+                Symbol::Rule(rule) => self.node(rule, start, mid, path)?,
+            };
+            let mut output = vec![tree];
+            output.extend(self.sequence(rest, mid, end, path)?);
+            Some(output)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recursive_rules() {
+        let mut grammar = Grammar::parse("0: 1 2\n1: \"a\"\n2: 1 3 | 3 1\n3: \"b\"");
+        assert!(grammar.matches(0, "aab"));
+        assert!(grammar.matches(0, "aba"));
+        assert!(!grammar.matches(0, "abb"));
+        assert_eq!(grammar.matching_rules("ab"), [2]);
+
+        //left recursive, right recursive and nested
+        grammar.set_rule("4: 4 1 | 1");
+        grammar.set_rule("5: 1 5 3 | 1 3");
+        assert!(grammar.matches(4, &"a".repeat(50)));
+        let nested = format!("{}{}", "a".repeat(40), "b".repeat(40));
+        assert!(grammar.matches(5, &nested));
+        assert!(!grammar.matches(5, &format!("{nested}b")));
+
+        let tree = grammar.parse_tree(0, "aab").unwrap();
+        assert_eq!(
+            tree.to_string(),
+            "0: \"aab\"\n  1: \"a\"\n    'a'\n  2: \"ab\"\n    1: \"a\"\n      'a'\n    3: \"b\"\n      'b'\n"
+        );
+        assert_eq!(grammar.parse_tree(0, "abb"), None);
+    }
+}
@@ -0,0 +1,420 @@
+use std::collections::{HashMap, HashSet};
+
+//one of the 8 ways to rotate and reflect a rectangle: transpose first if
+//asked, then mirror left to right and then top to bottom
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Symmetry {
+    pub transpose: bool,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl Symmetry {
+    pub const IDENTITY: Self = Self {
+        transpose: false,
+        flip_x: false,
+        flip_y: false,
+    };
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..8).map(|i| Self {
+            transpose: i & 4 != 0,
+            flip_x: i & 2 != 0,
+            flip_y: i & 1 != 0,
+        })
+    }
+
+    //the size of a width by height rectangle after the transformation
+    pub const fn size(self, (width, height): (usize, usize)) -> (usize, usize) {
+        if self.transpose {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    pub const fn apply(self, (x, y): (usize, usize), size: (usize, usize)) -> (usize, usize) {
+        let (mut x, mut y) = if self.transpose { (y, x) } else { (x, y) };
+        let (width, height) = self.size(size);
+        if self.flip_x {
+            x = width - 1 - x
+        }
+        if self.flip_y {
+            y = height - 1 - y
+        }
+        (x, y)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![false; width * height],
+        }
+    }
+
+    pub fn parse(input: &str) -> Self {
+        let rows: Vec<&str> = input.lines().collect();
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let mut output = Self::new(width, rows.len());
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                output.set((x, y), c == '#')
+            }
+        }
+        output
+    }
+
+    pub const fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height && self.cells[y * self.width + x]
+    }
+
+    pub fn set(&mut self, (x, y): (usize, usize), value: bool) {
+        self.cells[y * self.width + x] = value
+    }
+
+    pub fn set_points(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&p| self.get(p))
+    }
+
+    pub fn transformed(&self, symmetry: Symmetry) -> Self {
+        let (width, height) = symmetry.size(self.size());
+        let mut output = Self::new(width, height);
+        for p in self.set_points() {
+            output.set(symmetry.apply(p, self.size()), true)
+        }
+        output
+    }
+
+    fn read_edge(&self, points: impl Iterator<Item = (usize, usize)>) -> u32 {
+        points.fold(0, |acc, p| acc << 1 | self.get(p) as u32)
+    }
+
+    //top and bottom read left to right, left and right read top to bottom
+    fn edges(&self) -> Edges {
+        let (w, h) = (self.width, self.height);
+        Edges {
+            top: self.read_edge((0..w).map(|x| (x, 0))),
+            bottom: self.read_edge((0..w).map(|x| (x, h - 1))),
+            left: self.read_edge((0..h).map(|y| (0, y))),
+            right: self.read_edge((0..h).map(|y| (w - 1, y))),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Edges {
+    top: u32,
+    bottom: u32,
+    left: u32,
+    right: u32,
+}
+
+pub struct Tile {
+    pub id: u64,
+    pub image: Image,
+}
+
+impl Tile {
+    pub fn parse(input: &str) -> Self {
+        let (header, image) = input.split_once('\n').unwrap();
+        let id = header
+            .trim_start_matches("Tile ")
+            .trim_end_matches(':')
+            .parse()
+            .unwrap();
+        let image = Image::parse(image);
+        assert_eq!(image.width, image.height, "tile {id} isn't square");
+        assert!(image.width <= 32, "tile {id} is too big");
+        Self { id, image }
+    }
+}
+
+//the same border read in either direction gives the same key
+fn canonical(edge: u32, length: usize) -> u32 {
+    edge.min(edge.reverse_bits() >> (32 - length))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Placement {
+    pub tile: usize,
+    pub symmetry: Symmetry,
+}
+
+pub struct Puzzle {
+    tiles: Vec<Tile>,
+    tile_size: usize,
+    //edges of every tile in every orientation, in the order of Symmetry::all
+    edges: Vec<Vec<Edges>>,
+    by_edge: HashMap<u32, Vec<usize>>,
+}
+
+impl Puzzle {
+    pub fn new(tiles: Vec<Tile>) -> Self {
+        let tile_size = tiles[0].image.width;
+        assert!(tiles.iter().all(|t| t.image.width == tile_size));
+        let edges: Vec<Vec<Edges>> = tiles
+            .iter()
+            .map(|t| {
+                Symmetry::all()
+                    .map(|s| t.image.transformed(s).edges())
+                    .collect()
+            })
+            .collect();
+        let mut by_edge: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, e) in edges.iter().enumerate() {
+            let e = e[0];
+            for edge in [e.top, e.bottom, e.left, e.right] {
+                by_edge
+                    .entry(canonical(edge, tile_size))
+                    .or_default()
+                    .push(i)
+            }
+        }
+
+        Self {
+            tiles,
+            tile_size,
+            edges,
+            by_edge,
+        }
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    //how many of a tile's borders no other tile shares
+    fn unmatched_edges(&self, tile: usize) -> usize {
+        let e = self.edges[tile][0];
+        [e.top, e.bottom, e.left, e.right]
+            .into_iter()
+            .filter(|&edge| self.by_edge[&canonical(edge, self.tile_size)].len() == 1)
+            .count()
+    }
+
+    //lays the tiles out row by row. a tile's candidates come from the edge index
+    //for its left or upper neighbour, and if a choice leads nowhere it's undone
+    pub fn assemble(&self) -> Option<Assembly> {
+        let side = (1..=self.tiles.len())
+            .find(|s| s * s >= self.tiles.len())
+            .filter(|s| s * s == self.tiles.len())?;
+        let mut placements = vec![];
+        let mut used = vec![false; self.tiles.len()];
+        self.place(side, &mut placements, &mut used)
+            .then_some(Assembly { side, placements })
+    }
+
+    fn place(&self, side: usize, placements: &mut Vec<Placement>, used: &mut [bool]) -> bool {
+        let index = placements.len();
+        if index == self.tiles.len() {
+            return true;
+        }
+        let (x, y) = (index % side, index / side);
+        let edge = |p: &Placement| self.edges[p.tile][Self::symmetry_index(p.symmetry)];
+        let left = (x > 0).then(|| edge(&placements[index - 1]).right);
+        let above = (y > 0).then(|| edge(&placements[index - side]).bottom);
+
+        let mut candidates: Vec<usize> = match left.or(above) {
+            Some(e) => self.by_edge[&canonical(e, self.tile_size)].clone(),
+            //corner pieces are the most likely to fit in the corner
+            None => (0..self.tiles.len()).collect(),
+        };
+        candidates.sort_by_key(|&t| std::cmp::Reverse(self.unmatched_edges(t)));
+
+        for tile in candidates {
+            if used[tile] {
+                continue;
+            }
+            for (i, symmetry) in Symmetry::all().enumerate() {
+                let e = self.edges[tile][i];
+                if left.is_some_and(|l| l != e.left) || above.is_some_and(|a| a != e.top) {
+                    continue;
+                }
+                used[tile] = true;
+                placements.push(Placement { tile, symmetry });
+                if self.place(side, placements, used) {
+                    return true;
+                }
+                placements.pop();
+                used[tile] = false;
+            }
+        }
+
+        false
+    }
+
+    fn symmetry_index(symmetry: Symmetry) -> usize {
+        symmetry.transpose as usize * 4 + symmetry.flip_x as usize * 2 + symmetry.flip_y as usize
+    }
+
+    //every tile's orientation applied, borders removed and the insides joined up
+    pub fn image(&self, assembly: &Assembly) -> Image {
+        let inner = self.tile_size - 2;
+        let mut output = Image::new(assembly.side * inner, assembly.side * inner);
+        for (i, placement) in assembly.placements.iter().enumerate() {
+            let (tx, ty) = (i % assembly.side, i / assembly.side);
+            let image = self.tiles[placement.tile]
+                .image
+                .transformed(placement.symmetry);
+            for (x, y) in image.set_points() {
+                if (1..=inner).contains(&x) && (1..=inner).contains(&y) {
+                    output.set((tx * inner + x - 1, ty * inner + y - 1), true)
+                }
+            }
+        }
+        output
+    }
+}
+
+//the tiles row by row, top left first
+pub struct Assembly {
+    pub side: usize,
+    pub placements: Vec<Placement>,
+}
+
+impl Assembly {
+    pub fn get(&self, (x, y): (usize, usize)) -> Placement {
+        self.placements[y * self.side + x]
+    }
+
+    pub fn corners(&self) -> [Placement; 4] {
+        let last = self.side - 1;
+        [(0, 0), (last, 0), (0, last), (last, last)].map(|p| self.get(p))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Match {
+    pub symmetry: Symmetry,
+    //top left corner of the transformed pattern
+    pub position: (usize, usize),
+    pub points: Vec<(usize, usize)>,
+}
+
+//every place the pattern's # cells are all set in the image, in any orientation.
+//orientations that look the same for a symmetric pattern are only reported once
+pub fn find_pattern(image: &Image, pattern: &Image) -> Vec<Match> {
+    let mut output = vec![];
+    let mut seen: HashSet<Vec<(usize, usize)>> = HashSet::new();
+    for symmetry in Symmetry::all() {
+        let transformed = pattern.transformed(symmetry);
+        let (width, height) = transformed.size();
+        let offsets: Vec<(usize, usize)> = transformed.set_points().collect();
+        if !seen.insert(offsets.clone()) || width > image.width || height > image.height {
+            continue;
+        }
+        for y in 0..=image.height - height {
+            for x in 0..=image.width - width {
+                let points: Vec<(usize, usize)> =
+                    offsets.iter().map(|&(dx, dy)| (x + dx, y + dy)).collect();
+                if points.iter().all(|&p| image.get(p)) {
+                    output.push(Match {
+                        symmetry,
+                        position: (x, y),
+                        points,
+                    })
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetries() {
+        let image = Image::parse("##.\n...");
+        let all: HashSet<Vec<(usize, usize)>> = Symmetry::all()
+            .map(|s| image.transformed(s).set_points().collect())
+            .collect();
+        assert_eq!(all.len(), 8);
+        assert_eq!(image.transformed(Symmetry::IDENTITY), image);
+    }
+
+    #[test]
+    fn pattern() {
+        let image = Image::parse(".#..\n##..\n.#.#\n..##");
+        let pattern = Image::parse("#.\n##");
+        let found = find_pattern(&image, &pattern);
+        assert_eq!(found.len(), 3);
+        let points: HashSet<_> = found.iter().flat_map(|m| m.points.clone()).collect();
+        assert_eq!(points.len(), 7);
+    }
+
+    //cuts a random picture into overlapping tiles, scrambles them and puts it back together
+    #[test]
+    fn assemble() {
+        let mut state: u64 = 20;
+        let mut random = |below: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % below
+        };
+
+        let (side, size) = (4, 10);
+        let full = (size - 1) * side + 1;
+        let mut picture = Image::new(full, full);
+        for y in 0..full {
+            for x in 0..full {
+                picture.set((x, y), random(2) == 0)
+            }
+        }
+
+        let mut tiles = vec![];
+        for ty in 0..side {
+            for tx in 0..side {
+                let mut image = Image::new(size, size);
+                for y in 0..size {
+                    for x in 0..size {
+                        image.set(
+                            (x, y),
+                            picture.get((tx * (size - 1) + x, ty * (size - 1) + y)),
+                        )
+                    }
+                }
+                let symmetry = Symmetry::all().nth(random(8) as usize).unwrap();
+                let id = (ty * side + tx) as u64;
+                let image = image.transformed(symmetry);
+                tiles.insert(random(tiles.len() as u64 + 1) as usize, Tile { id, image });
+            }
+        }
+
+        let puzzle = Puzzle::new(tiles);
+        let assembly = puzzle.assemble().unwrap();
+        let ids: Vec<u64> = assembly
+            .placements
+            .iter()
+            .map(|p| puzzle.tiles()[p.tile].id)
+            .collect();
+        let expected: Vec<u64> = (0..16).collect();
+        assert!(Symmetry::all().any(|s| {
+            let moved: Vec<u64> = (0..16)
+                .map(|i| {
+                    let (x, y) = s.apply((i % side, i / side), (side, side));
+                    ids[y * side + x]
+                })
+                .collect();
+            moved == expected
+        }));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::packet::DecodeError;
+
+//reads bits most significant first from a stream of hex digits, pulling in one
+//digit at a time as they're needed
+pub struct BitReader<I> {
+    digits: I,
+    buffer: u64,
+    buffered: u32,
+    position: usize,
+}
+
+impl<I: Iterator<Item = char>> BitReader<I> {
+    pub fn new(digits: I) -> Self {
+        Self {
+            digits,
+            buffer: 0,
+            buffered: 0,
+            position: 0,
+        }
+    }
+
+    //the number of bits read so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    fn fill(&mut self) -> Result<bool, DecodeError> {
+        let Some(c) = self.digits.next() else {
+            return Ok(false);
+        };
+        let nibble = c.to_digit(16).ok_or(DecodeError::InvalidHex(c))?;
+        self.buffer = (self.buffer << 4) | nibble as u64;
+        self.buffered += 4;
+        Ok(true)
+    }
+
+    pub fn read(&mut self, count: u32) -> Result<u64, DecodeError> {
+        assert!(count <= 32);
+        while self.buffered < count {
+            if !self.fill()? {
+                return Err(DecodeError::Truncated {
+                    position: self.position + self.buffered as usize,
+                    needed: (count - self.buffered) as usize,
+                });
+            }
+        }
+
+        self.buffered -= count;
+        self.position += count as usize;
+        let output = (self.buffer >> self.buffered) & ((1 << count) - 1);
+        self.buffer &= (1 << self.buffered) - 1;
+        Ok(output)
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, DecodeError> {
+        self.read(1).map(|b| b == 1)
+    }
+
+    //true if everything left is zero, i.e. padding
+    pub fn only_zeroes_left(&mut self) -> Result<bool, DecodeError> {
+        while self.fill()? {}
+        Ok(self.buffer == 0)
+    }
+}
+
+#[derive(Default)]
+pub struct BitWriter {
+    bits: Vec<bool>,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn write(&mut self, value: u64, count: u32) {
+        self.bits
+            .extend((0..count).rev().map(|i| (value >> i) & 1 == 1))
+    }
+
+    pub fn append(&mut self, other: BitWriter) {
+        self.bits.extend(other.bits)
+    }
+
+    //pads with zeroes up to a whole number of bytes
+    pub fn to_hex(&self) -> String {
+        self.bits
+            .chunks(8)
+            .flat_map(|byte| [byte.get(..4).unwrap_or(byte), byte.get(4..).unwrap_or(&[])])
+            .map(|chunk| {
+                let nibble = (0..4).fold(0, |acc, i| {
+                    acc * 2 + chunk.get(i).copied().unwrap_or(false) as u32
+                });
+                char::from_digit(nibble, 16).unwrap().to_ascii_uppercase()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,571 @@
+use crate::bits::{BitReader, BitWriter};
+use std::fmt::{self, Display, Write};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidHex(char),
+    //ran out of input at bit `position` while `needed` more bits were wanted
+    Truncated { position: usize, needed: usize },
+    LiteralTooLarge { position: usize },
+    //the subpackets of a length type 0 operator went past the declared length
+    LengthMismatch { position: usize },
+    TrailingData,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidHex(c) => write!(f, "invalid hex digit {c:?}"),
+            Self::Truncated { position, needed } => {
+                write!(
+                    f,
+                    "input ended at bit {position}, {needed} more bits needed"
+                )
+            }
+            Self::LiteralTooLarge { position } => {
+                write!(
+                    f,
+                    "literal ending at bit {position} doesn't fit in 128 bits"
+                )
+            }
+            Self::LengthMismatch { position } => {
+                write!(
+                    f,
+                    "subpackets overran their declared length at bit {position}"
+                )
+            }
+            Self::TrailingData => write!(f, "nonzero bits after the outermost packet"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EvalError {
+    Overflow,
+    //a comparison without exactly two operands, or a min/max with none
+    Operands(Operation, usize),
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "value doesn't fit in 128 bits"),
+            Self::Operands(operation, count) => {
+                write!(f, "{} can't take {count} operands", operation.name())
+            }
+        }
+    }
+}
+
+//a packet with more subpackets than its length field can describe
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    TooMany(usize),
+    TooLong(usize),
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooMany(count) => {
+                write!(f, "{count} subpackets don't fit in an 11 bit count")
+            }
+            Self::TooLong(length) => {
+                write!(
+                    f,
+                    "{length} bits of subpackets don't fit in a 15 bit length"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Sum,
+    Product,
+    Min,
+    Max,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Operation {
+    const fn from_id(id: u64) -> Option<Self> {
+        match id {
+            0 => Some(Self::Sum),
+            1 => Some(Self::Product),
+            2 => Some(Self::Min),
+            3 => Some(Self::Max),
+            5 => Some(Self::GreaterThan),
+            6 => Some(Self::LessThan),
+            7 => Some(Self::EqualTo),
+            _ => None,
+        }
+    }
+
+    const fn id(self) -> u64 {
+        match self {
+            Self::Sum => 0,
+            Self::Product => 1,
+            Self::Min => 2,
+            Self::Max => 3,
+            Self::GreaterThan => 5,
+            Self::LessThan => 6,
+            Self::EqualTo => 7,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Product => "product",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::GreaterThan => "greater than",
+            Self::LessThan => "less than",
+            Self::EqualTo => "equal to",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LengthType {
+    Bits,
+    Count,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PacketType {
+    Literal(u128),
+    Operation {
+        operation: Operation,
+        length_type: LengthType,
+        subpackets: Vec<Packet>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packet {
+    pub version: u8,
+    pub packet_type: PacketType,
+}
+
+const LITERAL_ID: u64 = 4;
+
+impl Packet {
+    //decodes the outermost packet. anything after it must be zero padding
+    pub fn from_hex(input: &str) -> Result<Self, DecodeError> {
+        let mut reader = BitReader::new(input.trim().chars());
+        let output = Self::read(&mut reader)?;
+        if !reader.only_zeroes_left()? {
+            return Err(DecodeError::TrailingData);
+        }
+        Ok(output)
+    }
+
+    pub fn read<I: Iterator<Item = char>>(reader: &mut BitReader<I>) -> Result<Self, DecodeError> {
+        let version = reader.read(3)? as u8;
+        let id = reader.read(3)?;
+        let Some(operation) = Operation::from_id(id) else {
+            let mut value: u128 = 0;
+            loop {
+                let is_there_more = reader.read_bool()?;
+                let group = reader.read(4)? as u128;
+                if value.leading_zeros() < 4 {
+                    return Err(DecodeError::LiteralTooLarge {
+                        position: reader.position(),
+                    });
+                }
+                value = (value << 4) | group;
+                if !is_there_more {
+                    break;
+                }
+            }
+            return Ok(Self {
+                version,
+                packet_type: PacketType::Literal(value),
+            });
+        };
+
+        let mut subpackets = vec![];
+        let length_type = if reader.read_bool()? {
+            let count = reader.read(11)?;
+            for _ in 0..count {
+                subpackets.push(Self::read(reader)?)
+            }
+            LengthType::Count
+        } else {
+            let length = reader.read(15)? as usize;
+            let end = reader.position() + length;
+            while reader.position() < end {
+                subpackets.push(Self::read(reader)?)
+            }
+            if reader.position() != end {
+                return Err(DecodeError::LengthMismatch {
+                    position: reader.position(),
+                });
+            }
+            LengthType::Bits
+        };
+
+        Ok(Self {
+            version,
+            packet_type: PacketType::Operation {
+                operation,
+                length_type,
+                subpackets,
+            },
+        })
+    }
+
+    pub fn write(&self, writer: &mut BitWriter) -> Result<(), EncodeError> {
+        writer.write(self.version as u64, 3);
+        match &self.packet_type {
+            PacketType::Literal(value) => {
+                writer.write(LITERAL_ID, 3);
+                let groups = (128 - value.leading_zeros()).div_ceil(4).max(1);
+                for i in (0..groups).rev() {
+                    writer.write((i > 0) as u64, 1);
+                    writer.write(((value >> (4 * i)) & 0xf) as u64, 4);
+                }
+            }
+            PacketType::Operation {
+                operation,
+                length_type,
+                subpackets,
+            } => {
+                writer.write(operation.id(), 3);
+                match length_type {
+                    LengthType::Count => {
+                        if subpackets.len() >= 1 << 11 {
+                            return Err(EncodeError::TooMany(subpackets.len()));
+                        }
+                        writer.write(1, 1);
+                        writer.write(subpackets.len() as u64, 11);
+                        for packet in subpackets {
+                            packet.write(writer)?
+                        }
+                    }
+                    LengthType::Bits => {
+                        let mut inner = BitWriter::new();
+                        for packet in subpackets {
+                            packet.write(&mut inner)?
+                        }
+                        if inner.len() >= 1 << 15 {
+                            return Err(EncodeError::TooLong(inner.len()));
+                        }
+                        writer.write(0, 1);
+                        writer.write(inner.len() as u64, 15);
+                        writer.append(inner);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_hex(&self) -> Result<String, EncodeError> {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer)?;
+        Ok(writer.to_hex())
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        let mut total = self.version as u64;
+        if let PacketType::Operation { subpackets, .. } = &self.packet_type {
+            total += subpackets.iter().map(|p| p.version_sum()).sum::<u64>();
+        }
+
+        total
+    }
+
+    pub fn evaluate(&self) -> Result<u128, EvalError> {
+        let (operation, subpackets) = match &self.packet_type {
+            PacketType::Literal(x) => return Ok(*x),
+            PacketType::Operation {
+                operation,
+                subpackets,
+                ..
+            } => (*operation, subpackets),
+        };
+        let values = subpackets
+            .iter()
+            .map(|p| p.evaluate())
+            .collect::<Result<Vec<_>, _>>()?;
+        let operands = || EvalError::Operands(operation, values.len());
+
+        match operation {
+            Operation::Sum => values
+                .iter()
+                .try_fold(0u128, |acc, &x| acc.checked_add(x))
+                .ok_or(EvalError::Overflow),
+            Operation::Product => values
+                .iter()
+                .try_fold(1u128, |acc, &x| acc.checked_mul(x))
+                .ok_or(EvalError::Overflow),
+            Operation::Min => values.iter().copied().min().ok_or_else(operands),
+            Operation::Max => values.iter().copied().max().ok_or_else(operands),
+            Operation::GreaterThan | Operation::LessThan | Operation::EqualTo => {
+                let &[a, b] = &values[..] else {
+                    return Err(operands());
+                };
+                Ok(match operation {
+                    Operation::GreaterThan => a > b,
+                    Operation::LessThan => a < b,
+                    _ => a == b,
+                } as u128)
+            }
+        }
+    }
+
+    //one line per packet, indented under its operator
+    pub fn pretty(&self) -> String {
+        let mut output = String::new();
+        self.pretty_into(&mut output, "", "");
+        output
+    }
+
+    fn pretty_into(&self, output: &mut String, first: &str, rest: &str) {
+        match &self.packet_type {
+            PacketType::Literal(value) => {
+                writeln!(output, "{first}{value} (v{})", self.version).unwrap()
+            }
+            PacketType::Operation {
+                operation,
+                subpackets,
+                ..
+            } => {
+                writeln!(output, "{first}{} (v{})", operation.name(), self.version).unwrap();
+                for (i, packet) in subpackets.iter().enumerate() {
+                    let (branch, continuation) = if i + 1 == subpackets.len() {
+                        ("└── ", "    ")
+                    } else {
+                        ("├── ", "│   ")
+                    };
+                    packet.pretty_into(
+                        output,
+                        &format!("{rest}{branch}"),
+                        &format!("{rest}{continuation}"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+//the expression on one line, e.g. sum(1, product(2, 3))
+impl Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.packet_type {
+            PacketType::Literal(value) => write!(f, "{value}"),
+            PacketType::Operation {
+                operation,
+                subpackets,
+                ..
+            } => {
+                let symbol = match operation {
+                    Operation::Sum => "sum",
+                    Operation::Product => "product",
+                    Operation::Min => "min",
+                    Operation::Max => "max",
+                    Operation::GreaterThan => "gt",
+                    Operation::LessThan => "lt",
+                    Operation::EqualTo => "eq",
+                };
+                write!(f, "{symbol}(")?;
+                for (i, packet) in subpackets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{packet}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [(&str, u64, u128); 8] = [
+        ("C200B40A82", 14, 3),
+        ("04005AC33890", 8, 54),
+        ("880086C3E88112", 15, 7),
+        ("CE00C43D881120", 11, 9),
+        ("D8005AC2A8F0", 13, 1),
+        ("F600BC2D8F", 19, 0),
+        ("9C005AC2F8F0", 16, 0),
+        ("9C0141080250320F1802104A08", 20, 1),
+    ];
+
+    #[test]
+    fn examples() {
+        for (hex, version_sum, value) in EXAMPLES {
+            let packet = Packet::from_hex(hex).unwrap();
+            assert_eq!(packet.version_sum(), version_sum, "{hex}");
+            assert_eq!(packet.evaluate(), Ok(value), "{hex}");
+            assert_eq!(packet.to_hex().unwrap(), hex);
+        }
+
+        for (hex, version_sum) in [
+            ("8A004A801A8002F478", 16),
+            ("620080001611562C8802118E34", 12),
+            ("C0015000016115A2E0802F182340", 23),
+            ("A0016C880162017C3686B18A3D4780", 31),
+        ] {
+            let packet = Packet::from_hex(hex).unwrap();
+            assert_eq!(packet.version_sum(), version_sum, "{hex}");
+            assert_eq!(Packet::from_hex(&packet.to_hex().unwrap()), Ok(packet));
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Packet::from_hex("C200B40A8"),
+            Err(DecodeError::Truncated { .. })
+        ));
+        assert_eq!(
+            Packet::from_hex("D2FE2G"),
+            Err(DecodeError::InvalidHex('G'))
+        );
+        assert_eq!(
+            Packet::from_hex("C200B40A8201"),
+            Err(DecodeError::TrailingData)
+        );
+
+        let big = Packet {
+            version: 0,
+            packet_type: PacketType::Literal(u128::MAX),
+        };
+        assert_eq!(Packet::from_hex(&big.to_hex().unwrap()), Ok(big.clone()));
+        //one more group than fits in 128 bits
+        let mut writer = BitWriter::new();
+        writer.write(0, 3);
+        writer.write(LITERAL_ID, 3);
+        for _ in 0..32 {
+            writer.write(0b11111, 5);
+        }
+        writer.write(0b00001, 5);
+        assert!(matches!(
+            Packet::from_hex(&writer.to_hex()),
+            Err(DecodeError::LiteralTooLarge { .. })
+        ));
+
+        let product = Packet {
+            version: 0,
+            packet_type: PacketType::Operation {
+                operation: Operation::Product,
+                length_type: LengthType::Count,
+                subpackets: vec![big.clone(), big],
+            },
+        };
+        assert_eq!(product.evaluate(), Err(EvalError::Overflow));
+    }
+
+    #[test]
+    fn length_fields() {
+        let literal = |value| Packet {
+            version: 0,
+            packet_type: PacketType::Literal(value),
+        };
+        let operator = |length_type, subpackets| Packet {
+            version: 0,
+            packet_type: PacketType::Operation {
+                operation: Operation::Sum,
+                length_type,
+                subpackets,
+            },
+        };
+
+        let most = operator(LengthType::Count, vec![literal(1); 2047]);
+        assert_eq!(Packet::from_hex(&most.to_hex().unwrap()), Ok(most));
+        let too_many = operator(LengthType::Count, vec![literal(1); 2048]);
+        assert_eq!(too_many.to_hex(), Err(EncodeError::TooMany(2048)));
+
+        //11 bits for a one group literal and 16 for two
+        let mut subpackets = vec![literal(0); 2973];
+        subpackets.extend(vec![literal(16); 4]);
+        let longest = operator(LengthType::Bits, subpackets.clone());
+        assert_eq!(Packet::from_hex(&longest.to_hex().unwrap()), Ok(longest));
+        subpackets.push(literal(0));
+        let too_long = operator(LengthType::Bits, subpackets);
+        assert_eq!(too_long.to_hex(), Err(EncodeError::TooLong(32778)));
+    }
+
+    struct Rng(u64);
+
+    impl Rng {
+        //the top half of the state, as the low bits of an lcg hardly change
+        fn step(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0 >> 32
+        }
+
+        fn next(&mut self, below: u64) -> u64 {
+            self.step() % below
+        }
+
+        //every bit of a u128, from four draws
+        fn wide(&mut self) -> u128 {
+            (0..4).fold(0, |acc, _| acc << 32 | self.step() as u128)
+        }
+    }
+
+    fn random_packet(rng: &mut Rng, depth: u32) -> Packet {
+        let version = rng.next(8) as u8;
+        if depth == 0 || rng.next(3) == 0 {
+            let bits = rng.next(129) as u32;
+            let value = rng.wide().checked_shr(128 - bits).unwrap_or(0);
+            return Packet {
+                version,
+                packet_type: PacketType::Literal(value),
+            };
+        }
+
+        let operation = Operation::from_id([0, 1, 2, 3, 5, 6, 7][rng.next(7) as usize]).unwrap();
+        let length_type = if rng.next(2) == 0 {
+            LengthType::Bits
+        } else {
+            LengthType::Count
+        };
+        let subpackets = (0..rng.next(4))
+            .map(|_| random_packet(rng, depth - 1))
+            .collect();
+        Packet {
+            version,
+            packet_type: PacketType::Operation {
+                operation,
+                length_type,
+                subpackets,
+            },
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = Rng(16);
+        for _ in 0..500 {
+            let packet = random_packet(&mut rng, 4);
+            let hex = packet.to_hex().unwrap();
+            assert_eq!(Packet::from_hex(&hex), Ok(packet), "{hex}");
+        }
+
+        let values: Vec<u128> = (0..100).map(|_| rng.wide()).collect();
+        assert!(values.iter().any(|&x| x >> 127 == 1));
+        for value in values {
+            let packet = Packet {
+                version: 0,
+                packet_type: PacketType::Literal(value),
+            };
+            assert_eq!(Packet::from_hex(&packet.to_hex().unwrap()), Ok(packet));
+        }
+    }
+}
@@ -3,8 +3,8 @@ pub mod axis_direction;
 pub mod beacons;
 pub mod direction;
 pub mod position;
+pub mod registration;
 pub mod rotation;
 pub mod sensor;
 pub mod sensors;
 pub mod transformation;
-pub mod translation;
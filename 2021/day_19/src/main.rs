@@ -1,39 +1,22 @@
-use std::{fs::read_to_string, vec};
+use std::fs::read_to_string;
 
-use day_19::sensors::Sensors;
-use itertools::Itertools;
+use day_19::{registration::register, sensors::Sensors};
 
 fn solve(path: &str) -> (usize, u32) {
     let input = read_to_string(path).unwrap();
-    let mut sensors = Sensors::parse(&input).unwrap().1;
-
-    sensors.0.get_mut(0).unwrap().transformation = Some(vec![]);
-    while sensors
+    let sensors = Sensors::parse(&input).unwrap().1;
+    let scanners: Vec<_> = sensors
         .0
-        .iter()
-        .any(|s| s.transformation.is_none())
-    {
-        for [i, j] in (0..sensors.0.len())
-            .permutations(2)
-            .map(|v| [v[0], v[1]])
-        {
-            let sensor_i = &sensors.0[i];
-            let sensor_j = &sensors.0[j];
-            if sensor_i.transformation.is_some() {
-                continue;
-            }
-            let sensor_j_transformation = match &sensor_j.transformation {
-                Some(t) => t.clone(),
-                None => continue,
-            };
-            if let Some((rotation, translation)) = sensor_i.find_relative_transformation(sensor_j) {
-                sensors.0[i].transformation =
-                    Some([vec![(rotation, translation)], sensor_j_transformation].concat())
-            }
-        }
-    }
+        .into_iter()
+        .map(|s| s.beacons.into_iter().collect())
+        .collect();
+    let registration = register(&scanners)
+        .unwrap_or_else(|unplaced| panic!("couldn't place scanners {unplaced:?}"));
 
-    (sensors.count_unique(), sensors.max_manhattan_distance())
+    (
+        registration.beacons.len(),
+        registration.max_manhattan_distance(),
+    )
 }
 
 fn main() {
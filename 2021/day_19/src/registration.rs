@@ -0,0 +1,242 @@
+use crate::{position::Position, rotation::Rotation, transformation::Transformation};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+//how many beacons two scanners must share to count as overlapping
+const OVERLAP: usize = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Matrix(pub [[i32; 3]; 3]);
+
+impl Matrix {
+    pub const IDENTITY: Self = Self([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    //the 24 proper rotations, one for each choice of where +x and +y end up
+    pub fn all() -> Vec<Self> {
+        Rotation::all().into_iter().map(Into::into).collect()
+    }
+
+    //which for a rotation is also its inverse
+    pub fn transpose(self) -> Self {
+        Self(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.0[j][i])
+        }))
+    }
+
+    pub fn compose(self, other: Self) -> Self {
+        let mut output = [[0; 3]; 3];
+        for (i, row) in output.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| self.0[i][k] * other.0[k][j]).sum();
+            }
+        }
+        Self(output)
+    }
+}
+
+//the columns are where the rotation sends each axis
+impl From<Rotation> for Matrix {
+    fn from(rotation: Rotation) -> Self {
+        let columns = [
+            rotation.positive_x_maps_to,
+            rotation.positive_y_maps_to,
+            rotation.positive_z_maps_to(),
+        ]
+        .map(|d| d.base_position());
+        Self([
+            columns.map(|c| c.x),
+            columns.map(|c| c.y),
+            columns.map(|c| c.z),
+        ])
+    }
+}
+
+impl Transformation for Matrix {
+    fn apply(&self, p: Position) -> Position {
+        let [x, y, z] = self.0.map(|row| row[0] * p.x + row[1] * p.y + row[2] * p.z);
+        Position { x, y, z }
+    }
+}
+
+//takes a scanner's own coordinates to scanner 0's. the translation is where
+//the scanner itself sits
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Pose {
+    pub rotation: Matrix,
+    pub translation: Position,
+}
+
+impl Pose {
+    pub const ORIGIN: Self = Self {
+        rotation: Matrix::IDENTITY,
+        translation: Position { x: 0, y: 0, z: 0 },
+    };
+
+    //`other` first, then this one
+    pub fn compose(self, other: Self) -> Self {
+        Self {
+            rotation: self.rotation.compose(other.rotation),
+            translation: self.apply(other.translation),
+        }
+    }
+}
+
+impl Transformation for Pose {
+    fn apply(&self, position: Position) -> Position {
+        self.rotation.apply(position) + self.translation
+    }
+}
+
+fn squared_distance(a: Position, b: Position) -> i64 {
+    let d = a - b;
+    [d.x, d.y, d.z].map(|c| c as i64 * c as i64).iter().sum()
+}
+
+//the squared distances between every pair of a scanner's beacons, with counts.
+//they don't change under rotation or translation, so two scanners sharing 12
+//beacons share at least 66 of them
+fn fingerprint(beacons: &[Position]) -> HashMap<i64, usize> {
+    let mut output = HashMap::new();
+    for (i, &a) in beacons.iter().enumerate() {
+        for &b in &beacons[i + 1..] {
+            *output.entry(squared_distance(a, b)).or_default() += 1
+        }
+    }
+    output
+}
+
+fn shared_distances(a: &HashMap<i64, usize>, b: &HashMap<i64, usize>) -> usize {
+    a.iter()
+        .map(|(d, &count)| count.min(b.get(d).copied().unwrap_or(0)))
+        .sum()
+}
+
+//the pose taking `other`'s coordinates to `scanner`'s, if enough beacons line up.
+//every pairing of beacons votes for the translation it implies
+fn align(scanner: &[Position], other: &[Position]) -> Option<Pose> {
+    Matrix::all().into_iter().find_map(|rotation| {
+        let mut votes: HashMap<Position, usize> = HashMap::new();
+        for &b in other {
+            let rotated = rotation.apply(b);
+            for &a in scanner {
+                let count = votes.entry(a - rotated).or_default();
+                *count += 1;
+                if *count >= OVERLAP {
+                    return Some(Pose {
+                        rotation,
+                        translation: a - rotated,
+                    });
+                }
+            }
+        }
+        None
+    })
+}
+
+pub struct Registration {
+    pub poses: Vec<Pose>,
+    pub beacons: HashSet<Position>,
+}
+
+impl Registration {
+    pub fn max_manhattan_distance(&self) -> u32 {
+        self.poses
+            .iter()
+            .flat_map(|a| {
+                self.poses
+                    .iter()
+                    .map(|b| a.translation.manhattan_distance(b.translation))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+//places every scanner relative to scanner 0, working outwards from it. only
+//pairs whose fingerprints share enough distances are tried. on failure, gives
+//the scanners that couldn't be placed
+pub fn register(scanners: &[Vec<Position>]) -> Result<Registration, Vec<usize>> {
+    let needed = OVERLAP * (OVERLAP - 1) / 2;
+    let fingerprints: Vec<HashMap<i64, usize>> = scanners.iter().map(|s| fingerprint(s)).collect();
+
+    let mut poses: Vec<Option<Pose>> = vec![None; scanners.len()];
+    poses[0] = Some(Pose::ORIGIN);
+    let mut frontier = VecDeque::from([0]);
+    while let Some(i) = frontier.pop_front() {
+        let pose = poses[i].unwrap();
+        for j in 0..scanners.len() {
+            if poses[j].is_some() || shared_distances(&fingerprints[i], &fingerprints[j]) < needed {
+                continue;
+            }
+            if let Some(relative) = align(&scanners[i], &scanners[j]) {
+                poses[j] = Some(pose.compose(relative));
+                frontier.push_back(j);
+            }
+        }
+    }
+
+    let unplaced: Vec<usize> = (0..scanners.len())
+        .filter(|&i| poses[i].is_none())
+        .collect();
+    if !unplaced.is_empty() {
+        return Err(unplaced);
+    }
+
+    let poses: Vec<Pose> = poses.into_iter().flatten().collect();
+    let beacons = scanners
+        .iter()
+        .zip(&poses)
+        .flat_map(|(beacons, pose)| beacons.iter().map(|&b| pose.apply(b)))
+        .collect();
+    Ok(Registration { poses, beacons })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotations() {
+        let all = Matrix::all();
+        assert_eq!(all.len(), 24);
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), 24);
+        for &a in &all {
+            for &b in &all {
+                assert!(all.contains(&a.compose(b)));
+            }
+        }
+    }
+
+    #[test]
+    fn recovers_poses() {
+        let beacons: Vec<Position> = (0..40)
+            .map(|i: i32| Position::from((i * 37 % 101 - 50, i * i % 89 - 44, i * 53 % 97 - 48)))
+            .collect();
+        let all = Matrix::all();
+        let truth = [
+            Pose::ORIGIN,
+            Pose {
+                rotation: all[5],
+                translation: Position::from((68, -1246, -43)),
+            },
+            Pose {
+                rotation: all[17],
+                translation: Position::from((-92, -2380, -20)),
+            },
+        ];
+        //each scanner sees the beacons in its own coordinates
+        let scanners: Vec<Vec<Position>> = truth
+            .iter()
+            .map(|pose| {
+                let inverse = pose.rotation.transpose();
+                beacons
+                    .iter()
+                    .map(|&b| inverse.apply(b - pose.translation))
+                    .collect()
+            })
+            .collect();
+
+        let registration = register(&scanners).unwrap();
+        assert_eq!(registration.poses, truth);
+        assert_eq!(registration.beacons.len(), beacons.len());
+    }
+}
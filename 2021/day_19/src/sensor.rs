@@ -1,13 +1,9 @@
-use crate::{
-    beacons::Beacons, position::Position, rotation::Rotation, transformation::Transformation,
-    translation::Translation,
-};
+use crate::{beacons::Beacons, position::Position};
 use nom::{bytes, character, multi, sequence, IResult, Parser};
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Sensor {
     pub beacons: Beacons,
-    pub transformation: Option<Vec<(Rotation, Translation)>>,
 }
 
 impl Sensor {
@@ -22,21 +18,8 @@ impl Sensor {
         )
         .map(|positions| {
             let beacons = Beacons::from_iter(positions);
-            Self {
-                beacons,
-                transformation: None,
-            }
+            Self { beacons }
         })
         .parse(input)
     }
-
-    pub fn get_position(&self) -> Option<Position> {
-        let t = self.transformation.as_ref()?;
-        Some(t.apply(Position::default()))
-    }
-
-    pub fn find_relative_transformation(&self, other: &Self) -> Option<(Rotation, Translation)> {
-        self.beacons
-            .find_relative_transformation(&other.beacons)
-    }
 }
@@ -1,15 +1,9 @@
-use crate::{position::Position, rotation::Rotation, translation::Translation};
+use crate::{position::Position, rotation::Rotation};
 
 pub trait Transformation {
     fn apply(&self, position: Position) -> Position;
 }
 
-impl Transformation for Translation {
-    fn apply(&self, position: Position) -> Position {
-        position + self.origin_maps_to
-    }
-}
-
 impl Transformation for Rotation {
     fn apply(&self, position: Position) -> Position {
         let x_image_base = self.positive_x_maps_to.base_position() * position.x;
@@ -30,12 +24,6 @@ impl<T: Transformation> Transformation for Vec<T> {
     }
 }
 
-impl Transformation for (Rotation, Translation) {
-    fn apply(&self, position: Position) -> Position {
-        self.1.apply(self.0.apply(position))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
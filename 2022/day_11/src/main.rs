@@ -1,115 +1,71 @@
-use itertools::Itertools;
-use num::integer::lcm;
-use std::collections::{HashMap, VecDeque};
+use santas_little_helpers::{
+    expression::{BinaryOp, Expr, Precedence},
+    number::lcm_all,
+};
 use std::fs::read_to_string;
 
-#[derive(Clone, Debug)]
-enum ItemValue {
-    Normal(u32),
-    Big(HashMap<u32, u32>),
+//how worry is kept in check after each inspection
+#[derive(Clone, Copy)]
+enum Relief {
+    DivideByThree,
+    //worry modulo the lcm of the divisors, which every test still agrees with
+    Modulo(u64),
 }
 
-impl ItemValue {
-    fn new(n: u32, divisors: Vec<u32>) -> Self {
-        ItemValue::Big(divisors.into_iter().map(|d| (d, n.rem_euclid(d))).collect())
-    }
+struct Monkey {
+    items: Vec<u64>,
+    operation: Expr,
+    divisor: u64,
+    if_true: usize,
+    if_false: usize,
 }
 
-impl<T: Into<u32>> std::ops::Add<T> for ItemValue {
-    type Output = Self;
-
-    fn add(self, rhs: T) -> Self::Output {
-        let rhs = rhs.into();
-        match self {
-            ItemValue::Normal(x) => ItemValue::Normal(x + rhs),
-
-            ItemValue::Big(remainders) => ItemValue::Big(
-                remainders
-                    .iter()
-                    .map(|(&divisor, &remainder)| (divisor, (remainder + rhs).rem_euclid(divisor)))
-                    .collect(),
-            ),
-        }
-    }
+fn last_number<T: std::str::FromStr>(line: &str) -> T {
+    line.rsplit(' ').next().unwrap().parse().ok().unwrap()
 }
 
-impl<T: Into<u32>> std::ops::Mul<T> for ItemValue {
-    type Output = Self;
-
-    fn mul(self, rhs: T) -> Self::Output {
-        let rhs = rhs.into();
-        match self {
-            ItemValue::Normal(x) => ItemValue::Normal(x * rhs),
-
-            ItemValue::Big(remainders) => ItemValue::Big(
-                remainders
-                    .iter()
-                    .map(|(&divisor, &remainder)| (divisor, (remainder * rhs).rem_euclid(divisor)))
-                    .collect(),
-            ),
+impl Monkey {
+    fn parse(input: &str) -> Self {
+        let lines: Vec<&str> = input.lines().map(|l| l.trim()).collect();
+        let items = lines[1]
+            .strip_prefix("Starting items: ")
+            .unwrap()
+            .split(", ")
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        //anything made of old, numbers, + and *
+        let precedence = Precedence::new(&[&[BinaryOp::Add], &[BinaryOp::Mul]]);
+        let text = lines[2].strip_prefix("Operation: new = ").unwrap();
+        let operation = precedence
+            .parse(text)
+            .unwrap_or_else(|e| panic!("{text:?}: {e}"));
+        if let Err(e) = operation.evaluate::<i128>(&|name| (name == "old").then_some(0)) {
+            panic!("{text:?}: {e}")
         }
-    }
-}
 
-impl From<ItemValue> for u32 {
-    fn from(item_value: ItemValue) -> Self {
-        match item_value {
-            ItemValue::Normal(x) => x,
-            ItemValue::Big(remainders) => {
-                remainders
-                    .into_iter()
-                    .reduce(|(divisor_1, remainder_1), (divisor_2, remainder_2)| {
-                        (
-                            lcm(divisor_1, divisor_2),
-                            (0..divisor_2)
-                                .map(|n| divisor_1 * n + remainder_1)
-                                .find(|&n| n.rem_euclid(divisor_2) == remainder_2)
-                                .unwrap(),
-                        )
-                    })
-                    .unwrap()
-                    .1
-            }
+        Self {
+            items,
+            operation,
+            divisor: last_number(lines[3]),
+            if_true: last_number(lines[4]),
+            if_false: last_number(lines[5]),
         }
     }
-}
 
-#[derive(Clone)]
-enum Operation {
-    Add(u32),
-    Mul(u32),
-    Square,
-}
-
-impl Operation {
-    fn apply(&self, input: ItemValue) -> ItemValue {
-        match self {
-            Operation::Add(other) => input + *other,
-            Operation::Mul(other) => input * *other,
-            Operation::Square => input.clone() * input,
+    fn inspect(&self, old: u64, relief: Relief) -> u64 {
+        let new: i128 = self
+            .operation
+            .evaluate(&|name| (name == "old").then_some(old as i128))
+            .unwrap_or_else(|e| panic!("{e}"));
+        match relief {
+            Relief::DivideByThree => (new / 3) as u64,
+            Relief::Modulo(m) => new.rem_euclid(m as i128) as u64,
         }
     }
-}
-
-#[derive(Clone)]
-struct DivisibilityTest {
-    divisor: u32,
-    if_true: usize,
-    if_false: usize,
-}
-
-impl DivisibilityTest {
-    fn apply(&self, input: &ItemValue) -> usize {
-        let result = match input {
-            ItemValue::Big(remainders) => remainders
-                .get(&self.divisor)
-                .map(|&n| n == 0)
-                .unwrap_or(false),
-
-            ItemValue::Normal(n) => n.rem_euclid(self.divisor) == 0,
-        };
 
-        if result {
+    fn target(&self, worry: u64) -> usize {
+        if worry.is_multiple_of(self.divisor) {
             self.if_true
         } else {
             self.if_false
@@ -117,157 +73,176 @@ impl DivisibilityTest {
     }
 }
 
-#[derive(Clone)]
-struct Monkey {
-    activity: u64,
-    items: VecDeque<ItemValue>,
-    operation: Operation,
-    divisibility_test: DivisibilityTest,
-}
+//an item on its own, between rounds: which monkey has it and how worrying it is
+type Item = (usize, u64);
 
-fn main() {
-    let input = read_to_string("input").unwrap();
+struct Troop(Vec<Monkey>);
 
-    let monkeys_1 = process_input(&input, true);
-    let monkeys_2 = process_input(&input, false);
-
-    let output_1 = simulate_simians(monkeys_1, 20, true);
-    let output_2 = simulate_simians(monkeys_2, 10000, false);
-
-    println!("part 1: {output_1} part 2: {output_2}");
-}
+impl Troop {
+    fn new(input: &str) -> Self {
+        Self(input.split("\n\n").map(Monkey::parse).collect())
+    }
 
-fn process_input(input: &str, divide_worry_levels: bool) -> Vec<Monkey> {
-    let divisors = if divide_worry_levels {
-        None
-    } else {
-        Some(
-            input
-                .lines()
-                .map(|s| s.trim())
-                .filter_map(|s| s.strip_prefix("Test: divisible by "))
-                .map(|s| s.parse().unwrap())
-                .collect(),
-        )
-    };
-    input
-        .split("\n\n")
-        .map(|s| generate_monkey(s, divisors.clone()))
-        .collect()
-}
+    fn lcm(&self) -> u64 {
+        lcm_all(self.0.iter().map(|m| m.divisor))
+    }
 
-fn round(monkeys: &mut [Monkey], divide_worry_level: bool) {
-    (0..monkeys.len()).for_each(|i| turn(monkeys, i, divide_worry_level))
-}
+    fn items(&self) -> impl Iterator<Item = Item> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .flat_map(|(i, m)| m.items.iter().map(move |&w| (i, w)))
+    }
 
-fn turn(monkeys: &mut [Monkey], monkey_index: usize, divide_worry_level: bool) {
-    let monkey = monkeys.get_mut(monkey_index).unwrap();
-    let mut items_to_throw: Vec<(ItemValue, usize)> = vec![];
-    while let Some(mut item_value) = monkey.items.pop_front() {
-        item_value = monkey.operation.apply(item_value);
-        if divide_worry_level {
-            if let ItemValue::Normal(x) = item_value {
-                item_value = ItemValue::Normal(x / 3)
+    //items never affect each other, so a round can be played one item at a time.
+    //it keeps being passed on within the round until it goes to a monkey whose
+    //turn has already been
+    fn round(&self, (mut monkey, mut worry): Item, relief: Relief, activity: &mut [u64]) -> Item {
+        loop {
+            activity[monkey] += 1;
+            worry = self.0[monkey].inspect(worry, relief);
+            let next = self.0[monkey].target(worry);
+            if next < monkey {
+                return (next, worry);
             }
+            monkey = next;
         }
-        let next_monkey_index = monkey.divisibility_test.apply(&item_value);
-        items_to_throw.push((item_value, next_monkey_index));
-        monkey.activity += 1;
     }
 
-    for (item_value, next_monkey_index) in items_to_throw {
-        monkeys[next_monkey_index].items.push_back(item_value)
+    fn rounds(&self, mut item: Item, rounds: u64, relief: Relief, activity: &mut [u64]) -> Item {
+        for _ in 0..rounds {
+            item = self.round(item, relief, activity)
+        }
+        item
     }
-}
 
-fn generate_monkey(input: &str, divisors: Option<Vec<u32>>) -> Monkey {
-    let lines = input.lines().collect_vec();
-    let items: VecDeque<ItemValue> = get_starting_items(lines[1], &divisors);
-    let operation = get_operation(lines[2]);
-    let divisibility_test = get_divisibility_test(&lines[3..]);
+    //brent's algorithm on one item's state at the start of each round: the
+    //rounds before it starts repeating, then the length of the repeat
+    fn find_cycle(&self, start: Item, relief: Relief) -> (u64, u64) {
+        let mut ignored = vec![0; self.0.len()];
+        let mut step = |item| self.round(item, relief, &mut ignored);
+
+        let (mut power, mut length) = (1, 1);
+        let mut tortoise = start;
+        let mut hare = step(start);
+        while tortoise != hare {
+            if power == length {
+                tortoise = hare;
+                power *= 2;
+                length = 0;
+            }
+            hare = step(hare);
+            length += 1;
+        }
 
-    Monkey {
-        activity: 0,
-        items,
-        operation,
-        divisibility_test,
+        let mut tortoise = start;
+        let mut hare = (0..length).fold(start, |item, _| step(item));
+        let mut before = 0;
+        while tortoise != hare {
+            tortoise = step(tortoise);
+            hare = step(hare);
+            before += 1;
+        }
+        (before, length)
     }
-}
-
-fn get_starting_items(input: &str, divisors: &Option<Vec<u32>>) -> VecDeque<ItemValue> {
-    input
-        .trim()
-        .strip_prefix("Starting items: ")
-        .unwrap()
-        .split(", ")
-        .map(|s| s.parse().unwrap())
-        .map(|n| match divisors {
-            None => ItemValue::Normal(n),
-            Some(divisors) => ItemValue::new(n, divisors.clone()),
-        })
-        .collect()
-}
-
-fn get_operation(input: &str) -> Operation {
-    let text_to_process = input.trim().strip_prefix("Operation: new = ").unwrap();
 
-    if text_to_process == "old * old" {
-        return Operation::Square;
-    };
-
-    if text_to_process.starts_with("old *") {
-        return Operation::Mul(
-            text_to_process
-                .strip_prefix("old * ")
-                .unwrap()
-                .parse()
-                .unwrap(),
-        );
-    };
+    //inspections per monkey, playing every round
+    fn simulate(&self, rounds: u64, relief: Relief) -> Vec<u64> {
+        let mut activity = vec![0; self.0.len()];
+        for item in self.items() {
+            self.rounds(item, rounds, relief, &mut activity);
+        }
+        activity
+    }
 
-    Operation::Add(
-        text_to_process
-            .strip_prefix("old + ")
-            .unwrap()
-            .parse()
-            .unwrap(),
-    )
+    //the same, but each item only plays up to the end of its first repeat and
+    //the rest is multiplied out, so the number of rounds doesn't matter
+    fn simulate_with_cycles(&self, rounds: u64) -> Vec<u64> {
+        let relief = Relief::Modulo(self.lcm());
+        let mut activity = vec![0; self.0.len()];
+        for item in self.items() {
+            let (before, length) = self.find_cycle(item, relief);
+            let item = self.rounds(item, rounds.min(before), relief, &mut activity);
+            let Some(left) = rounds.checked_sub(before) else {
+                continue;
+            };
+
+            let mut cycle = vec![0; self.0.len()];
+            self.rounds(item, length, relief, &mut cycle);
+            for (total, once) in activity.iter_mut().zip(cycle) {
+                *total += once * (left / length)
+            }
+            self.rounds(item, left % length, relief, &mut activity);
+        }
+        activity
+    }
 }
 
-fn get_divisibility_test(input: &[&str]) -> DivisibilityTest {
-    let divisor: u32 = input[0]
-        .trim()
-        .strip_prefix("Test: divisible by ")
-        .unwrap()
-        .parse()
-        .unwrap();
-
-    let if_true = input[1]
-        .trim()
-        .strip_prefix("If true: throw to monkey ")
-        .unwrap()
-        .parse()
-        .unwrap();
-
-    let if_false = input[2]
-        .trim()
-        .strip_prefix("If false: throw to monkey ")
-        .unwrap()
-        .parse()
-        .unwrap();
-
-    DivisibilityTest {
-        divisor,
-        if_true,
-        if_false,
-    }
+//u128, as two counts in the trillions don't multiply in a u64
+fn monkey_business(mut activity: Vec<u64>) -> u128 {
+    activity.sort_unstable_by(|a, b| b.cmp(a));
+    activity.iter().take(2).map(|&x| x as u128).product()
 }
 
-fn simulate_simians(mut monkeys: Vec<Monkey>, round_count: u32, divide_worry_level: bool) -> u64 {
-    for _ in 0..round_count {
-        round(&mut monkeys, divide_worry_level);
-    }
-    let monkey_business: u64 = monkeys.iter().map(|m| m.activity).k_largest(2).product();
+fn main() {
+    let input = read_to_string("input").unwrap();
+    let troop = Troop::new(&input);
 
-    monkey_business
+    let output_1 = monkey_business(troop.simulate(20, Relief::DivideByThree));
+    let output_2 = monkey_business(troop.simulate_with_cycles(10000));
+    println!("part 1: {output_1} part 2: {output_2}");
+    println!(
+        "after 10^12 rounds: {}",
+        monkey_business(troop.simulate_with_cycles(1_000_000_000_000))
+    );
+}
+
+#[test]
+fn example() {
+    let input = "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+    let troop = Troop::new(input);
+    assert_eq!(
+        monkey_business(troop.simulate(20, Relief::DivideByThree)),
+        10605
+    );
+
+    let modulo = Relief::Modulo(troop.lcm());
+    assert_eq!(troop.simulate_with_cycles(20), troop.simulate(20, modulo));
+    assert_eq!(troop.simulate_with_cycles(1000), [5204, 4792, 199, 5192]);
+    assert_eq!(
+        monkey_business(troop.simulate_with_cycles(10000)),
+        2713310158
+    );
+    assert_eq!(
+        monkey_business(troop.simulate_with_cycles(1_000_000_000_000)),
+        27142382301385558311211320
+    );
 }
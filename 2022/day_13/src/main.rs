@@ -1,99 +1,29 @@
 use itertools::{chain, Itertools};
+use santas_little_helpers::value::Value;
 use std::cmp::Ordering;
 use std::fs::read_to_string;
 
 #[derive(Clone)]
-enum Token {
-    OpenBracket,
-    CloseBracket,
-    Integer(u32),
-}
-
-struct Tokens(Vec<Token>);
-
-impl<T: Into<String>> From<T> for Tokens {
-    fn from(value: T) -> Self {
-        let value = value.into();
-        Tokens({
-            value
-                .split(',')
-                .flat_map(|s| {
-                    let open_bracket_count = s.chars().take_while(|&c| c == '[').count();
-                    let close_bracket_count = s.chars().skip_while(|&c| c != ']').count();
-
-                    let value = if s.trim_matches(|c| c == '[' || c == ']').is_empty() {
-                        vec![]
-                    } else {
-                        vec![Token::Integer(
-                            s.trim_matches(|c| c == '[' || c == ']').parse().unwrap(),
-                        )]
-                    };
-
-                    chain!(
-                        vec![Token::OpenBracket; open_bracket_count],
-                        value,
-                        vec![Token::CloseBracket; close_bracket_count]
-                    )
-                })
-                .collect()
-        })
-    }
-}
-
-#[derive(Eq, Clone)]
-enum Packet {
-    Integer(u32),
-    List(Vec<Packet>),
-}
-
-impl From<Tokens> for Packet {
-    fn from(Tokens(value): Tokens) -> Self {
-        let mut packets: Vec<Packet> = vec![];
-        for token in value {
-            match token {
-                Token::Integer(x) => {
-                    if let Packet::List(v) = packets.last_mut().unwrap() {
-                        v.push(Packet::Integer(x))
-                    } else {
-                        panic!("Integer packet on stack")
-                    }
-                }
-
-                Token::OpenBracket => packets.push(Packet::new()),
-
-                Token::CloseBracket => {
-                    let finished_packet = packets.pop().unwrap();
-                    match packets.last_mut() {
-                        None => return finished_packet,
-
-                        Some(Packet::List(v)) => v.push(finished_packet),
-
-                        _ => {
-                            panic!("Integer packet on stack")
-                        }
-                    };
-                }
-            }
-        }
-
-        packets.into_iter().next().unwrap()
-    }
-}
-
-impl<T: Into<String>> From<T> for Packet {
-    fn from(value: T) -> Self {
-        Packet::from(Tokens::from(value))
+struct Packet(Value);
+
+fn compare(left: &Value, right: &Value) -> Ordering {
+    match (left, right) {
+        (Value::Number(x), Value::Number(y)) => x.cmp(y),
+        (Value::List(v1), Value::List(v2)) => v1
+            .iter()
+            .zip(v2)
+            .map(|(x, y)| compare(x, y))
+            .find(|&o| o != Ordering::Equal)
+            .unwrap_or(v1.len().cmp(&v2.len())),
+        (Value::Number(_), Value::List(_)) => compare(&Value::List(vec![left.clone()]), right),
+        (Value::List(_), Value::Number(_)) => compare(right, left).reverse(),
+        _ => panic!("packets only contain lists and integers"),
     }
 }
 
 impl Ord for Packet {
     fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (Packet::Integer(x), Packet::Integer(y)) => x.cmp(y),
-            (Packet::List(v1), Packet::List(v2)) => v1.cmp(v2),
-            (Packet::Integer(x), Packet::List(v)) => vec![Packet::Integer(*x)].cmp(v),
-            (p1, p2) => p2.cmp(p1).reverse(),
-        }
+        compare(&self.0, &other.0)
     }
 }
 
@@ -105,18 +35,15 @@ impl PartialOrd for Packet {
 
 impl PartialEq for Packet {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Packet::Integer(x), Packet::Integer(y)) => x.eq(y),
-            (Packet::List(v1), Packet::List(v2)) => v1.eq(v2),
-            (Packet::Integer(x), Packet::List(v)) => vec![Packet::Integer(*x)].eq(v),
-            (p1, p2) => p2.eq(p1),
-        }
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl Packet {
-    fn new() -> Self {
-        Packet::List(vec![])
+impl Eq for Packet {}
+
+impl From<&str> for Packet {
+    fn from(value: &str) -> Self {
+        Packet(value.parse().unwrap_or_else(|e| panic!("{e}")))
     }
 }
 
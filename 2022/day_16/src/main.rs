@@ -1,243 +1,237 @@
-use itertools::{izip, Itertools};
-use regex::Regex;
-use std::cmp::max;
-use std::collections::{HashMap, HashSet, VecDeque};
+use santas_little_helpers::branch_and_bound::{maximise, maximise_all, Problem, Solution, Stats};
+use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::time::Instant;
 
-#[derive(Clone)]
-struct Valve {
-    flow_rate: u32,
-    reachable_valves: HashMap<String, u32>,
+//only the valves worth opening, numbered by bit, plus the tunnel distances
+//between them and from the start
+struct Cave {
+    flow_rates: Vec<u64>,
+    distances: Vec<Vec<u64>>,
+    start: usize,
 }
 
-#[derive(Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
-struct Agent {
-    position: String,
-    sleeping_for: u32,
-}
-
-impl Agent {
-    fn new(position: &str) -> Self {
-        Agent {
-            position: position.to_string(),
-            sleeping_for: 0,
+impl Cave {
+    fn new(input: &str) -> Self {
+        let mut names: HashMap<&str, usize> = HashMap::new();
+        let mut valves = vec![];
+        for line in input.lines().filter(|l| !l.is_empty()) {
+            let words: Vec<&str> = line
+                .split([' ', '=', ';', ','])
+                .filter(|w| !w.is_empty())
+                .collect();
+            let flow_rate: u64 = words[5].parse().unwrap();
+            let tunnels: Vec<&str> = words[10..].to_vec();
+            names.insert(words[1], valves.len());
+            valves.push((words[1], flow_rate, tunnels));
         }
-    }
-}
-
-#[derive(Eq, PartialEq, Hash, Clone)]
-struct State {
-    unopened_valves: Vec<String>,
 
-    agents: Vec<Agent>,
-    remaining_time: u32,
-}
+        //floyd warshall over every valve, then keep the ones that matter
+        let n = valves.len();
+        let mut all = vec![vec![u64::MAX / 2; n]; n];
+        for (i, (_, _, tunnels)) in valves.iter().enumerate() {
+            all[i][i] = 0;
+            for t in tunnels {
+                all[i][names[t]] = 1
+            }
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    all[i][j] = all[i][j].min(all[i][k] + all[k][j])
+                }
+            }
+        }
 
-impl State {
-    fn new(valves: &HashMap<String, Valve>, number_of_agents: usize, remaining_time: u32) -> Self {
-        State {
-            unopened_valves: valves
+        let mut kept: Vec<usize> = (0..n).filter(|&i| valves[i].1 > 0).collect();
+        let start = kept.len();
+        kept.push(names["AA"]);
+        assert!(
+            start < 64,
+            "{start} valves with a flow rate don't fit in a u64 mask"
+        );
+        Self {
+            flow_rates: kept[..start].iter().map(|&i| valves[i].1).collect(),
+            distances: kept
                 .iter()
-                .filter(|(_, v)| v.flow_rate != 0)
-                .map(|(s, _)| s)
-                .cloned()
+                .map(|&i| kept.iter().map(|&j| all[i][j]).collect())
                 .collect(),
-            agents: vec![Agent::new("AA"); number_of_agents],
-            remaining_time,
+            start,
         }
     }
 
-    fn possible_actions(&self) -> Vec<Vec<String>> {
-        self.agents
-            .iter()
-            .map(|a| {
-                if a.sleeping_for == 0 {
-                    self.unopened_valves.clone()
-                } else {
-                    vec![a.position.clone()]
-                }
-            })
-            .multi_cartesian_product()
-            .collect()
+    fn valves(&self) -> usize {
+        self.flow_rates.len()
     }
 
-    fn apply_actions(&self, actions: &Vec<String>, valves: &HashMap<String, Valve>) -> (Self, u32) {
-        let mut new_state = self.clone();
-        let mut pressure_released = 0;
-        for (action, agent) in izip!(actions, new_state.agents.iter_mut()) {
-            if agent.sleeping_for > 0 {
-                continue;
-            }
-            if !new_state.unopened_valves.contains(action) {
-                continue;
-            }
-            agent.sleeping_for = valves[&agent.position.clone()].reachable_valves[action] + 1;
-            agent.position.clone_from(action);
-            let remaining_time = self.remaining_time.saturating_sub(agent.sleeping_for);
-            let flow_rate = valves[action].flow_rate;
-            pressure_released += remaining_time * flow_rate;
-
-            new_state.unopened_valves.swap_remove(
-                new_state
-                    .unopened_valves
-                    .iter()
-                    .position(|x| x == action)
-                    .unwrap(),
-            );
-        }
-
-        let sleep_time = new_state
-            .agents
-            .iter()
-            .map(|a| a.sleeping_for)
-            .min()
-            .unwrap();
+    fn single(&self, allowed: u64, time: u64) -> Solution {
+        let state = Walk {
+            position: self.start,
+            opened: 0,
+            time_left: time,
+        };
+        maximise(
+            &Route {
+                cave: self,
+                allowed,
+            },
+            state,
+        )
+    }
 
-        for agent in new_state.agents.iter_mut() {
-            agent.sleeping_for = agent.sleeping_for.saturating_sub(sleep_time)
+    //every agent takes its own set of valves, so the best team is the best
+    //split of the valves, each part scored by one agent alone
+    fn max_pressure(&self, agents: usize, time: u64) -> (u64, Stats) {
+        assert!(agents >= 1, "no one to open the valves");
+        let all = (1u64 << self.valves()) - 1;
+        if agents == 1 {
+            let solution = self.single(all, time);
+            return (solution.score, solution.stats);
         }
-
-        new_state.remaining_time = new_state.remaining_time.saturating_sub(sleep_time);
-        new_state.agents.sort();
-        (new_state, pressure_released)
+        //every subset of the valves is solved and kept, so this can't go much higher
+        assert!(
+            self.valves() <= 20,
+            "{} valves is over the cap of 20 for more than one agent: sharing them means \
+             solving all 2^{} subsets",
+            self.valves(),
+            self.valves()
+        );
+
+        let start = Walk {
+            position: self.start,
+            opened: 0,
+            time_left: time,
+        };
+        let solutions = maximise_all(
+            (0..=all)
+                .map(|allowed| {
+                    (
+                        Route {
+                            cave: self,
+                            allowed,
+                        },
+                        start,
+                    )
+                })
+                .collect(),
+        );
+        let mut stats = Stats::default();
+        for solution in &solutions {
+            stats += solution.stats
+        }
+        let single: Vec<u64> = solutions.iter().map(|s| s.score).collect();
+
+        //best[mask] for a team of k agents sharing the valves in mask
+        let mut best = single.clone();
+        for _ in 1..agents {
+            best = (0..=all)
+                .map(|mask| {
+                    let mut output = 0;
+                    let mut part = mask;
+                    loop {
+                        output = output.max(single[part as usize] + best[(mask ^ part) as usize]);
+                        if part == 0 {
+                            break;
+                        }
+                        part = (part - 1) & mask;
+                    }
+                    output
+                })
+                .collect();
+        }
+        (best[all as usize], stats)
     }
+}
 
-    fn potential(&self, valves: &HashMap<String, Valve>) -> u32 {
-        self.unopened_valves
-            .iter()
-            .map(|s| valves[s].flow_rate)
-            .sum::<u32>()
-            * (self.remaining_time.saturating_sub(1))
-    }
+#[derive(Clone, Copy)]
+struct Walk {
+    position: usize,
+    opened: u64,
+    time_left: u64,
 }
 
-struct Valves {
-    valves: HashMap<String, Valve>,
+//one agent opening valves from `allowed`
+struct Route<'a> {
+    cave: &'a Cave,
+    allowed: u64,
 }
 
-impl Valves {
-    fn new(input: &str) -> Self {
-        let valves: HashMap<String, Valve> = input
-            .lines()
-            .map(|line| {
-                let int_pat = Regex::new(r"\d+").unwrap();
-                let valve_pat = Regex::new(r"[A-Z][A-Z]").unwrap();
-                let flow_rate: u32 = int_pat.find(line).unwrap().as_str().parse().unwrap();
-                let current_valve = valve_pat.find(line).unwrap().as_str().to_string();
-                let reachable_valves: HashMap<String, u32> = valve_pat
-                    .find_iter(line)
-                    .skip(1)
-                    .map(|m| (m.as_str().to_string(), 1))
-                    .collect();
-
-                (
-                    current_valve,
-                    Valve {
-                        flow_rate,
-                        reachable_valves,
-                    },
-                )
+impl Route<'_> {
+    //each closed valve with the time that would be left once it's open, if any
+    fn reachable(&self, walk: &Walk) -> impl Iterator<Item = (usize, u64)> + '_ {
+        let closed = self.allowed & !walk.opened;
+        let distances = &self.cave.distances[walk.position];
+        let time_left = walk.time_left;
+        (0..self.cave.valves())
+            .filter(move |&v| closed & (1 << v) != 0)
+            .filter_map(move |v| {
+                let remaining = time_left.checked_sub(distances[v] + 1)?;
+                (remaining > 0).then_some((v, remaining))
             })
-            .collect();
-
-        Valves { valves }.get_complete_graph()
     }
+}
 
-    fn get_complete_graph(self) -> Self {
-        let mut important_valves: HashMap<String, Valve> = self
-            .valves
-            .iter()
-            .filter(|(s, v)| s == &"AA" || v.flow_rate > 0)
-            .map(|(x, y)| (x.clone(), y.clone()))
+impl Problem for Route<'_> {
+    type State = Walk;
+    type Key = (usize, u64, u64);
+
+    fn moves(&self, walk: &Walk) -> Vec<(Walk, u64)> {
+        let mut output: Vec<(Walk, u64)> = self
+            .reachable(walk)
+            .map(|(v, time_left)| {
+                let next = Walk {
+                    position: v,
+                    opened: walk.opened | 1 << v,
+                    time_left,
+                };
+                (next, self.cave.flow_rates[v] * time_left)
+            })
             .collect();
-
-        for valve in important_valves.values_mut() {
-            valve.reachable_valves.clear()
-        }
-
-        for (start, end) in important_valves
-            .clone()
-            .keys()
-            .permutations(2)
-            .map(|v| (v[0].clone(), v[1].clone()))
-        {
-            important_valves
-                .get_mut(&start)
-                .unwrap()
-                .reachable_valves
-                .insert(end.clone(), self.get_distance(start, end));
-        }
-
-        Valves {
-            valves: important_valves,
-        }
+        output.sort_by_key(|&(_, gain)| std::cmp::Reverse(gain));
+        output
     }
 
-    fn get_distance(&self, start: String, end: String) -> u32 {
-        let mut frontier = VecDeque::from([(start, 0)]);
-        let mut visited = HashSet::new();
-        while let Some((valve_name, distance)) = frontier.pop_front() {
-            if !visited.insert(valve_name.clone()) {
-                continue;
-            }
-            if valve_name == end {
-                return distance;
-            }
-
-            for next in self.valves[&valve_name].reachable_valves.keys() {
-                frontier.push_back((next.clone(), distance + 1))
-            }
-        }
-        panic!("no path found")
+    //as if every closed valve could be walked to straight from here
+    fn bound(&self, walk: &Walk) -> u64 {
+        self.reachable(walk)
+            .map(|(v, time_left)| self.cave.flow_rates[v] * time_left)
+            .sum()
     }
 
-    fn get_max_pressure(
-        &self,
-        state: State,
-        max_so_far: &mut u32,
-        value_of_getting_here: u32,
-        visited: &mut HashSet<State>,
-    ) -> u32 {
-        if !visited.insert(state.clone()) {
-            return *max_so_far;
-        }
-        *max_so_far = max(*max_so_far, value_of_getting_here);
-        if state.remaining_time == 0 {
-            return *max_so_far;
-        };
-        if state.potential(&self.valves) + value_of_getting_here <= *max_so_far {
-            return *max_so_far;
-        };
-
-        for action in state.possible_actions() {
-            let (next, value) = state.apply_actions(&action, &self.valves);
-            let next_value =
-                self.get_max_pressure(next, max_so_far, value_of_getting_here + value, visited);
-            *max_so_far = max(*max_so_far, next_value);
-        }
-
-        *max_so_far
+    fn key(&self, walk: &Walk) -> Option<Self::Key> {
+        Some((walk.position, walk.opened, walk.time_left))
     }
 }
 
 fn main() {
     let timer = Instant::now();
     let input = read_to_string("input").unwrap();
-    let cave = Valves::new(&input);
-
-    let output_1 = cave.get_max_pressure(
-        State::new(&cave.valves, 1, 30),
-        &mut 0,
-        0,
-        &mut HashSet::new(),
-    );
-    let output_2 = cave.get_max_pressure(
-        State::new(&cave.valves, 2, 26),
-        &mut 0,
-        0,
-        &mut HashSet::new(),
-    );
+    let cave = Cave::new(&input);
+
+    let (output_1, stats_1) = cave.max_pressure(1, 30);
+    let (output_2, stats_2) = cave.max_pressure(2, 26);
+    println!("part 1: {stats_1}");
+    println!("part 2: {stats_2}");
     println!("part 1: {output_1} part 2: {output_2}");
     println!("time: {}", timer.elapsed().as_secs_f32());
 }
+
+#[test]
+fn example() {
+    let input = "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II
+";
+    let cave = Cave::new(input);
+    assert_eq!(cave.max_pressure(1, 30).0, 1651);
+    assert_eq!(cave.max_pressure(2, 26).0, 1707);
+    assert_eq!(cave.max_pressure(3, 26).0, 1794);
+}
@@ -1,124 +1,76 @@
+use santas_little_helpers::{
+    cuboids::{Cuboid, CuboidSet},
+    vector::Vector,
+};
 use std::{collections::HashSet, fs::read_to_string};
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
-struct Position {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl Position {
-    fn get_neighbours(&self) -> Vec<Position> {
-        vec![
-            Position {
-                x: self.x + 1,
-                ..*self
-            },
-            Position {
-                x: self.x - 1,
-                ..*self
-            },
-            Position {
-                y: self.y + 1,
-                ..*self
-            },
-            Position {
-                y: self.y - 1,
-                ..*self
-            },
-            Position {
-                z: self.z + 1,
-                ..*self
-            },
-            Position {
-                z: self.z - 1,
-                ..*self
-            },
-        ]
-    }
+type Position = Vector<3>;
 
-    fn new(input: &str) -> Self {
-        let coords = input.split(',').collect::<Vec<_>>();
-        let x = coords[0].parse().unwrap();
-        let y = coords[1].parse().unwrap();
-        let z = coords[2].parse().unwrap();
-        Position { x, y, z }
-    }
-}
 struct Droplet {
     positions: HashSet<Position>,
+    bounds: Cuboid<3>,
 }
 
 impl Droplet {
     fn new(input: &str) -> Self {
-        let positions = input.lines().map(Position::new).collect();
+        let positions: HashSet<Position> =
+            input.lines().map(|l| Vector::parse(l).unwrap()).collect();
+        let min = [0, 1, 2].map(|i| positions.iter().map(|p| p[i]).min().unwrap() - 1);
+        let max = [0, 1, 2].map(|i| positions.iter().map(|p| p[i]).max().unwrap() + 2);
+        let bounds = Cuboid::new(min, max);
 
-        Droplet { positions }
+        Droplet { positions, bounds }
     }
 
     fn get_external_points(&self) -> HashSet<Position> {
-        let min_x = self.positions.iter().map(|p| p.x).min().unwrap() - 1;
-        let max_x = self.positions.iter().map(|p| p.x).max().unwrap() + 1;
-        let min_y = self.positions.iter().map(|p| p.y).min().unwrap() - 1;
-        let max_y = self.positions.iter().map(|p| p.y).max().unwrap() + 1;
-        let min_z = self.positions.iter().map(|p| p.z).min().unwrap() - 1;
-        let max_z = self.positions.iter().map(|p| p.z).max().unwrap() + 1;
-
         let mut visited = HashSet::new();
-        let mut frontier = vec![Position {
-            x: min_x,
-            y: min_y,
-            z: min_z,
-        }];
+        let mut frontier = vec![Vector::new(self.bounds.min)];
 
         while let Some(pos) = frontier.pop() {
             if self.positions.contains(&pos) {
                 continue;
             };
+            if !self.bounds.contains(pos.0) {
+                continue;
+            }
             if !visited.insert(pos) {
                 continue;
             };
 
-            if pos.x < min_x
-                || pos.x > max_x
-                || pos.y < min_y
-                || pos.y > max_y
-                || pos.z < min_z
-                || pos.z > max_z
-            {
-                continue;
-            }
-
-            frontier.extend(pos.get_neighbours())
+            frontier.extend(pos.neighbours())
         }
 
         visited
     }
+
+    fn cubes(&self) -> CuboidSet<3> {
+        self.positions.iter().map(|p| Cuboid::unit(p.0)).collect()
+    }
+
+    //the droplet with its air pockets filled in, so only the exterior surface is left
+    fn filled(&self) -> CuboidSet<3> {
+        let external_points = self.get_external_points();
+        let mut output = self.cubes();
+        for x in self.bounds.min[0]..self.bounds.max[0] {
+            for y in self.bounds.min[1]..self.bounds.max[1] {
+                for z in self.bounds.min[2]..self.bounds.max[2] {
+                    let pos = Vector::new([x, y, z]);
+                    if !self.positions.contains(&pos) && !external_points.contains(&pos) {
+                        output.add(Cuboid::unit(pos.0));
+                    }
+                }
+            }
+        }
+
+        output
+    }
 }
 
 fn main() {
     let input = read_to_string("input").unwrap();
     let droplet: Droplet = Droplet::new(&input);
-    let output_1 = (droplet.positions.iter())
-        .flat_map(|p| {
-            p.get_neighbours()
-                .into_iter()
-                .filter(|p_2| !droplet.positions.contains(p_2))
-                .collect::<Vec<_>>()
-        })
-        .count();
-
-    let external_points = droplet.get_external_points();
-
-    let output_2 = droplet
-        .positions
-        .iter()
-        .flat_map(|p| {
-            p.get_neighbours()
-                .into_iter()
-                .filter(|p_2| external_points.contains(p_2))
-        })
-        .count();
+    let output_1 = droplet.cubes().surface_area();
+    let output_2 = droplet.filled().surface_area();
 
     println!();
     println!("part 1: {output_1} part 2: {output_2}");
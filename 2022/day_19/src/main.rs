@@ -1,247 +1,169 @@
-use kdam::TqdmIterator;
-use regex::Regex;
-use std::cmp::max;
-use std::collections::HashSet;
+use santas_little_helpers::branch_and_bound::{maximise_all, Problem, Solution, Stats};
 use std::fs::read_to_string;
 
-enum Action {
-    DoNothing,
-    BuildOreCollecting,
-    BuildClayCollecting,
-    BuildGeodeCracking,
-    BuildObsidianCollecting,
-}
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
-struct Resources {
-    ore: u16,
-    clay: u16,
-    obsidian: u16,
+//geodes aren't kept: a geode robot is scored for everything it will crack the
+//moment it's built
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    remaining_time: u16,
+    resources: [u16; 3],
+    robots: [u16; 3],
 }
 
-impl Resources {
-    const fn new() -> Self {
+impl State {
+    const fn new(remaining_time: u16) -> Self {
         Self {
-            ore: 0,
-            clay: 0,
-            obsidian: 0,
+            remaining_time,
+            resources: [0; 3],
+            robots: [1, 0, 0],
         }
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
-struct Robots {
-    ore_collecting: u16,
-    clay_collecting: u16,
-    obsidian_collecting: u16,
-    geode_cracking: u16,
+//the cost of each robot in ore, clay and obsidian
+#[derive(Clone, Copy)]
+struct Blueprint {
+    id: u16,
+    costs: [[u16; 3]; 4],
 }
 
-impl Robots {
-    const fn new() -> Self {
+impl Blueprint {
+    fn new(input: &str) -> Self {
+        let nums: Vec<u16> = input
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().unwrap())
+            .collect();
         Self {
-            ore_collecting: 1,
-            clay_collecting: 0,
-            obsidian_collecting: 0,
-            geode_cracking: 0,
+            id: nums[0],
+            costs: [
+                [nums[1], 0, 0],
+                [nums[2], 0, 0],
+                [nums[3], nums[4], 0],
+                [nums[5], 0, nums[6]],
+            ],
         }
     }
-}
-
-#[derive(Clone, PartialEq, Eq, Hash)]
-struct State {
-    remaining_time: u16,
-    resources: Resources,
-    robots: Robots,
-}
 
-impl State {
-    const fn new(remaining_time: u16) -> Self {
-        Self {
-            remaining_time,
-            resources: Resources::new(),
-            robots: Robots::new(),
-        }
+    //a robot per minute already covers the most that can be spent per minute
+    fn most_useful(&self, resource: usize) -> u16 {
+        self.costs.iter().map(|c| c[resource]).max().unwrap()
     }
 
-    fn get_max_geodes(
-        &self,
-        max_so_far: &mut u16,
-        current_geodes: u16,
-        blueprint: &Blueprint,
-        visited: &mut HashSet<Self>,
-    ) -> u16 {
-        *max_so_far = max(*max_so_far, current_geodes);
-        if !visited.insert(self.clone()) {
-            return *max_so_far;
-        }
-        if self.remaining_time == 0 {
-            return *max_so_far;
-        };
-        if self.get_potential() + current_geodes <= *max_so_far {
-            return *max_so_far;
-        };
-
-        for action in self.get_possible_actions(blueprint) {
-            let (new_state, geodes) = self.apply_action(action, blueprint);
-            let new_geodes =
-                new_state.get_max_geodes(max_so_far, current_geodes + geodes, blueprint, visited);
-
-            *max_so_far = max(*max_so_far, new_geodes);
+    //minutes spent waiting for the resources, then one more to build
+    fn time_to_build(&self, state: &State, robot: usize) -> Option<u16> {
+        let mut wait = 0;
+        for resource in ORE..=OBSIDIAN {
+            let missing = self.costs[robot][resource].saturating_sub(state.resources[resource]);
+            if missing == 0 {
+                continue;
+            }
+            if state.robots[resource] == 0 {
+                return None;
+            }
+            wait = wait.max(missing.div_ceil(state.robots[resource]));
         }
-
-        *max_so_far
+        Some(wait + 1)
     }
+}
 
-    fn get_possible_actions(&self, blueprint: &Blueprint) -> Vec<Action> {
-        let mut output = vec![];
-        let max_ore_cost = [
-            blueprint.obsidian_robot_cost.0,
-            blueprint.ore_robot_cost,
-            blueprint.clay_robot_cost,
-            blueprint.geode_robot_cost.0,
-        ]
-        .into_iter()
-        .max()
-        .unwrap();
-        if self.resources.ore >= blueprint.geode_robot_cost.0
-            && self.resources.obsidian >= blueprint.geode_robot_cost.1
-        {
-            output.push(Action::BuildGeodeCracking);
-        };
-        if self.resources.ore >= blueprint.obsidian_robot_cost.0
-            && self.resources.clay >= blueprint.obsidian_robot_cost.1
-            && self.robots.obsidian_collecting < blueprint.geode_robot_cost.1
-        {
-            output.push(Action::BuildObsidianCollecting);
-        };
-        if self.resources.ore >= blueprint.clay_robot_cost
-            && self.robots.clay_collecting < blueprint.obsidian_robot_cost.1
-        {
-            output.push(Action::BuildClayCollecting);
-        };
-        if self.resources.ore >= blueprint.ore_robot_cost
-            && self.robots.ore_collecting < max_ore_cost
-        {
-            output.push(Action::BuildOreCollecting);
-        };
-
-        if output.len() < 4 {
-            output.push(Action::DoNothing);
-        }
-
-        output
-    }
+impl Problem for Blueprint {
+    type State = State;
+    type Key = State;
 
-    fn apply_action(&self, action: Action, blueprint: &Blueprint) -> (Self, u16) {
-        let mut new_state = self.clone();
-        let mut geodes = 0;
-        new_state.resources.ore += new_state.robots.ore_collecting;
-        new_state.resources.clay += new_state.robots.clay_collecting;
-        new_state.resources.obsidian += new_state.robots.obsidian_collecting;
-        new_state.remaining_time -= 1;
-
-        match action {
-            Action::BuildGeodeCracking => {
-                new_state.resources.ore -= blueprint.geode_robot_cost.0;
-                new_state.resources.obsidian -= blueprint.geode_robot_cost.1;
-                new_state.robots.geode_cracking += 1;
-                geodes = new_state.remaining_time;
+    //skip straight to building each kind of robot next
+    fn moves(&self, state: &State) -> Vec<(State, u64)> {
+        let mut output = vec![];
+        for robot in [GEODE, OBSIDIAN, CLAY, ORE] {
+            if robot != GEODE && state.robots[robot] >= self.most_useful(robot) {
+                continue;
             }
-
-            Action::BuildClayCollecting => {
-                new_state.resources.ore -= blueprint.clay_robot_cost;
-                new_state.robots.clay_collecting += 1;
+            let Some(time) = self.time_to_build(state, robot) else {
+                continue;
+            };
+            let Some(remaining_time) = state.remaining_time.checked_sub(time) else {
+                continue;
+            };
+            if remaining_time == 0 {
+                continue;
             }
 
-            Action::BuildObsidianCollecting => {
-                new_state.resources.ore -= blueprint.obsidian_robot_cost.0;
-                new_state.resources.clay -= blueprint.obsidian_robot_cost.1;
-                new_state.robots.obsidian_collecting += 1;
+            let mut next = State {
+                remaining_time,
+                ..*state
+            };
+            for resource in ORE..=OBSIDIAN {
+                next.resources[resource] += state.robots[resource] * time;
+                next.resources[resource] -= self.costs[robot][resource];
             }
-
-            Action::BuildOreCollecting => {
-                new_state.resources.ore -= blueprint.ore_robot_cost;
-                new_state.robots.ore_collecting += 1;
+            if robot == GEODE {
+                output.push((next, remaining_time as u64))
+            } else {
+                next.robots[robot] += 1;
+                output.push((next, 0))
             }
-
-            Action::DoNothing => {}
-        };
-
-        (new_state, geodes)
-    }
-
-    const fn get_potential(&self) -> u16 {
-        self.remaining_time * (self.remaining_time - 1) / 2
-    }
-}
-
-struct Blueprint {
-    id: u16,
-    ore_robot_cost: u16,
-    clay_robot_cost: u16,
-    obsidian_robot_cost: (u16, u16),
-    geode_robot_cost: (u16, u16),
-}
-
-impl Blueprint {
-    fn get_quality_level(&self, time: u16) -> u16 {
-        let max_number_of_geodes = self.get_max_geodes(time);
-        max_number_of_geodes * self.id
+        }
+        output
     }
 
-    fn get_max_geodes(&self, time: u16) -> u16 {
-        let start_state = State::new(time);
-        start_state.get_max_geodes(&mut 0, 0, self, &mut HashSet::new())
+    //a new geode robot every minute from now on
+    fn bound(&self, state: &State) -> u64 {
+        let t = state.remaining_time as u64;
+        t * t.saturating_sub(1) / 2
     }
 
-    fn new(input: &str) -> Self {
-        let pat = Regex::new(r"\d+").unwrap();
-        let nums: Vec<u16> = pat
-            .find_iter(input)
-            .map(|m| m.as_str().parse().unwrap())
-            .collect();
-        let id = nums[0];
-        let ore_robot_cost = nums[1];
-        let clay_robot_cost = nums[2];
-        let obsidian_robot_cost = (nums[3], nums[4]);
-        let geode_robot_cost = (nums[5], nums[6]);
-
-        Self {
-            id,
-            obsidian_robot_cost,
-            geode_robot_cost,
-            ore_robot_cost,
-            clay_robot_cost,
-        }
+    fn key(&self, state: &State) -> Option<State> {
+        Some(*state)
     }
 }
 
-struct Blueprints(Vec<Blueprint>);
-
-impl Blueprints {
-    fn new(input: &str) -> Self {
-        let blueprints = Self(input.lines().map(Blueprint::new).collect());
-        blueprints
+fn max_geodes(blueprints: &[Blueprint], time: u16) -> (Vec<Solution>, Stats) {
+    let solutions = maximise_all(blueprints.iter().map(|&b| (b, State::new(time))).collect());
+    let mut stats = Stats::default();
+    for solution in &solutions {
+        stats += solution.stats
     }
+    (solutions, stats)
 }
 
 fn main() {
     let input = read_to_string("input").unwrap();
-    let blueprints: Blueprints = Blueprints::new(&input);
-    let output_1: u16 = blueprints
-        .0
+    let blueprints: Vec<Blueprint> = input.lines().map(Blueprint::new).collect();
+
+    let (solutions, stats) = max_geodes(&blueprints, 24);
+    println!("part 1: {stats}");
+    let output_1: u64 = blueprints
         .iter()
-        .tqdm()
-        .map(|b| b.get_quality_level(24))
+        .zip(&solutions)
+        .map(|(b, s)| b.id as u64 * s.score)
         .sum();
-    println!("{output_1}");
-    let output_2: u16 = blueprints
-        .0
-        .iter()
-        .take(3)
-        .tqdm()
-        .map(|b| b.get_max_geodes(32))
-        .product();
+
+    let first = &blueprints[..blueprints.len().min(3)];
+    let (solutions, stats) = max_geodes(first, 32);
+    println!("part 2: {stats}");
+    let output_2: u64 = solutions.iter().map(|s| s.score).product();
     println!("part 1: {output_1}, part 2: {output_2}")
 }
+
+#[test]
+fn example() {
+    let blueprints = [
+        "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.",
+        "Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.",
+    ]
+    .map(Blueprint::new);
+    let scores = |time| {
+        max_geodes(&blueprints, time)
+            .0
+            .iter()
+            .map(|s| s.score)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(scores(24), [9, 12]);
+    assert_eq!(scores(32), [56, 62]);
+}
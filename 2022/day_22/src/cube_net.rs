@@ -0,0 +1,426 @@
+use crate::{
+    direction::{DiagonalDirection, Direction},
+    position::Position,
+};
+use itertools::Itertools;
+use santas_little_helpers::union_find::KeyedUnionFind;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NetError {
+    //the number of filled cells isn't 6 * n * n
+    FaceSize(usize),
+    //a face-sized block is only partly filled
+    RaggedFace(Position),
+    FaceCount(usize),
+    NotACube,
+}
+
+//the layout of the faces of a cube, unfolded onto the page.
+//face positions are measured in whole faces, not cells
+#[derive(Clone, Debug)]
+pub struct CubeNet {
+    pub face_size: i32,
+    faces: Vec<Position>,
+    width: i32,
+    height: i32,
+}
+
+impl CubeNet {
+    pub fn parse(input: &str) -> Result<Self, NetError> {
+        let cells = input
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .map(|s| s.chars().collect_vec())
+            .collect_vec();
+
+        let num_cells = cells.iter().flatten().filter(|&&c| c != ' ').count();
+        let face_size = (1..)
+            .take_while(|n| 6 * n * n <= num_cells)
+            .find(|n| 6 * n * n == num_cells)
+            .ok_or(NetError::FaceSize(num_cells))?;
+
+        let is_filled = |x: usize, y: usize| {
+            cells
+                .get(y)
+                .and_then(|v| v.get(x))
+                .is_some_and(|&c| c != ' ')
+        };
+
+        let height = cells.len().div_ceil(face_size);
+        let width = cells
+            .iter()
+            .map(|v| v.len())
+            .max()
+            .unwrap_or_default()
+            .div_ceil(face_size);
+        let mut faces = vec![];
+        for (y, x) in (0..height).cartesian_product(0..width) {
+            let block = (y * face_size..(y + 1) * face_size)
+                .cartesian_product(x * face_size..(x + 1) * face_size)
+                .map(|(y, x)| is_filled(x, y))
+                .collect_vec();
+
+            let position = Position::new(x as i32, y as i32);
+            if block.iter().all(|&b| b) {
+                faces.push(position);
+            } else if block.iter().any(|&b| b) {
+                return Err(NetError::RaggedFace(position));
+            }
+        }
+
+        if faces.len() != 6 {
+            return Err(NetError::FaceCount(faces.len()));
+        }
+
+        Ok(Self {
+            face_size: face_size as i32,
+            faces,
+            width: width as i32,
+            height: height as i32,
+        })
+    }
+
+    pub fn faces(&self) -> &[Position] {
+        &self.faces
+    }
+
+    //walking off an edge carries on across the page, wrapping around to the
+    //next face in the same row or column
+    pub fn flat_edges(&self) -> EdgeMap {
+        let edges = self
+            .faces
+            .iter()
+            .cartesian_product(Direction::all())
+            .map(|(&p, d)| {
+                let mut current = p;
+                loop {
+                    current = current.step(d).wrap_around(self.width, self.height);
+                    if self.faces.contains(&current) {
+                        break;
+                    }
+                }
+                ((p, d), (current, d.opposite()))
+            })
+            .collect();
+
+        EdgeMap(edges)
+    }
+
+    pub fn fold(&self) -> Result<EdgeMap, NetError> {
+        let mut partitions = Partitions::new(&self.faces);
+        partitions.cubify();
+        if !partitions.is_cube() {
+            return Err(NetError::NotACube);
+        }
+
+        Ok(EdgeMap(partitions.get_edge_map()))
+    }
+
+    //draws each face as a capital letter, surrounded by the letters of the faces
+    //that its edges are glued to
+    pub fn render(&self, edges: &EdgeMap) -> String {
+        let name =
+            |p: Position| (b'A' + self.faces.iter().position(|&f| f == p).unwrap() as u8) as char;
+        let mut output = String::new();
+        for y in 0..self.height {
+            let rows = [Direction::Up, Direction::Left, Direction::Down].map(|d| {
+                (0..self.width)
+                    .map(|x| {
+                        let p = Position::new(x, y);
+                        if !self.faces.contains(&p) {
+                            return "     ".to_string();
+                        }
+                        let neighbour = |d| name(edges.get(p, d).0).to_ascii_lowercase();
+                        match d {
+                            Direction::Left => format!(
+                                "{} {} {}",
+                                neighbour(Direction::Left),
+                                name(p),
+                                neighbour(Direction::Right)
+                            ),
+                            _ => format!("+-{}-+", neighbour(d)),
+                        }
+                    })
+                    .join(" ")
+            });
+
+            for row in rows {
+                output.push_str(row.trim_end());
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Crossing {
+    pub face: Position,
+    pub facing: Direction,
+    //clockwise quarter turns between the old and new facing
+    pub turns: u32,
+}
+
+//for each edge of each face, the face and edge it is glued to
+#[derive(Clone, Debug)]
+pub struct EdgeMap(HashMap<(Position, Direction), (Position, Direction)>);
+
+impl EdgeMap {
+    pub fn get(&self, face: Position, edge: Direction) -> (Position, Direction) {
+        self.0[&(face, edge)]
+    }
+
+    pub fn cross(&self, face: Position, facing: Direction) -> Crossing {
+        let (face, edge) = self.get(face, facing);
+        let new_facing = edge.opposite();
+        Crossing {
+            face,
+            facing: new_facing,
+            turns: facing.turns_to(new_facing),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&(Position, Direction), &(Position, Direction))> {
+        self.0.iter()
+    }
+}
+
+struct Partitions {
+    edges: KeyedUnionFind<(Position, Direction)>,
+    corners: KeyedUnionFind<(Position, DiagonalDirection)>,
+}
+
+impl Partitions {
+    fn new(positions: &[Position]) -> Self {
+        let edges = positions
+            .iter()
+            .flat_map(|&p| Direction::all().into_iter().map(move |d| (p, d)))
+            .collect();
+
+        let corners = positions
+            .iter()
+            .flat_map(|&p| DiagonalDirection::all().into_iter().map(move |d| (p, d)))
+            .collect();
+
+        Self { edges, corners }
+    }
+
+    fn merge_edges(&mut self, edge_1: (Position, Direction), edge_2: (Position, Direction)) {
+        let (corner_1_1, corner_1_2) = (
+            (edge_1.0, edge_1.1.get_diagonals().0),
+            (edge_1.0, edge_1.1.get_diagonals().1),
+        );
+
+        let (corner_2_1, corner_2_2) = (
+            (edge_2.0, edge_2.1.get_diagonals().0),
+            (edge_2.0, edge_2.1.get_diagonals().1),
+        );
+
+        self.edges.union(edge_1, edge_2);
+        self.corners.union(corner_1_1, corner_2_2);
+        self.corners.union(corner_1_2, corner_2_1);
+    }
+
+    fn merge_overlapping_edges(&mut self) {
+        let positions = self.edges.keys().iter().map(|&(p, _)| p).collect_vec();
+
+        for position in positions.clone() {
+            for direction in Direction::all() {
+                let other_position = position.step(direction);
+                if positions.contains(&other_position) {
+                    self.merge_edges(
+                        (position, direction),
+                        (other_position, direction.opposite()),
+                    )
+                }
+            }
+        }
+    }
+
+    fn get_touching_edges(
+        &mut self,
+        corners: Vec<(Position, DiagonalDirection)>,
+    ) -> Vec<Vec<(Position, Direction)>> {
+        let edges = corners
+            .iter()
+            .flat_map(|&(p, d)| {
+                let (direction_1, direction_2) = d.get_directions();
+                [(p, direction_1), (p, direction_2)]
+            })
+            .collect_vec();
+
+        let mut output = vec![];
+        for edge in edges {
+            let class = self.edges.component(edge);
+            if !output.contains(&class) {
+                output.push(class)
+            }
+        }
+
+        output
+    }
+
+    fn get_corner_that_needs_merging(&mut self) -> Option<Vec<(Position, DiagonalDirection)>> {
+        self.corners.components().into_iter().find(|corners| {
+            corners.len() == 3 && self.get_touching_edges(corners.clone()).len() == 4
+        })
+    }
+
+    fn merge_corner(&mut self, corner: Vec<(Position, DiagonalDirection)>) {
+        let edges = self.get_touching_edges(corner.clone());
+        let singletons = edges.iter().filter(|s| s.len() == 1).collect_vec();
+        let edge_1 = *singletons[0].iter().next().unwrap();
+        let edge_2 = *singletons[1].iter().next().unwrap();
+        self.merge_edges(edge_1, edge_2);
+    }
+
+    fn cubify(&mut self) {
+        self.merge_overlapping_edges();
+        while let Some(c) = self.get_corner_that_needs_merging() {
+            self.merge_corner(c);
+        }
+    }
+
+    //a cube has 12 edges, each shared by 2 faces, and 8 corners, each shared by 3
+    fn is_cube(&mut self) -> bool {
+        let edges = self.edges.components();
+        let corners = self.corners.components();
+        edges.len() == 12
+            && edges.iter().all(|v| v.len() == 2 && v[0].0 != v[1].0)
+            && corners.len() == 8
+            && corners.iter().all(|v| v.len() == 3)
+    }
+
+    fn get_edge_map(&mut self) -> HashMap<(Position, Direction), (Position, Direction)> {
+        self.edges
+            .components()
+            .iter()
+            .flat_map(|x| x.iter().permutations(2))
+            .map(|v| (*v[0], *v[1]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETS: [&str; 11] = [
+        "#...\n####\n#...",
+        "#...\n####\n.#..",
+        "#...\n####\n..#.",
+        "#...\n####\n...#",
+        ".#..\n####\n.#..",
+        ".#..\n####\n..#.",
+        "##..\n.###\n.#..",
+        "##..\n.###\n..#.",
+        "##..\n.###\n...#",
+        "##..\n.##.\n..##",
+        "###..\n..###",
+    ];
+
+    fn symmetries(net: &str) -> Vec<Vec<Vec<char>>> {
+        let grid = net
+            .lines()
+            .map(|l| {
+                l.chars()
+                    .map(|c| if c == '#' { '.' } else { ' ' })
+                    .collect_vec()
+            })
+            .collect_vec();
+        let rotate = |g: &Vec<Vec<char>>| {
+            (0..g[0].len())
+                .map(|x| (0..g.len()).rev().map(|y| g[y][x]).collect_vec())
+                .collect_vec()
+        };
+        let mirror = |g: &Vec<Vec<char>>| {
+            g.iter()
+                .map(|row| row.iter().rev().copied().collect_vec())
+                .collect_vec()
+        };
+
+        let mut output = vec![grid.clone(), mirror(&grid)];
+        for _ in 0..3 {
+            let next = rotate(output.last().unwrap());
+            output.push(mirror(&next));
+            output.push(next);
+        }
+        output
+    }
+
+    fn scale(grid: &[Vec<char>], face_size: usize) -> String {
+        grid.iter()
+            .flat_map(|row| {
+                let line = row
+                    .iter()
+                    .flat_map(|&c| std::iter::repeat_n(c, face_size))
+                    .collect::<String>();
+                std::iter::repeat_n(line, face_size)
+            })
+            .join("\n")
+    }
+
+    #[test]
+    fn all_nets_fold() {
+        for net in NETS {
+            for grid in symmetries(net) {
+                let input = scale(&grid, 3) + "\n\n10R5";
+                let net = CubeNet::parse(&input).unwrap();
+                assert_eq!(net.face_size, 3);
+
+                let edges = net.fold().unwrap();
+                for (&(face, edge), &(other_face, other_edge)) in edges.iter() {
+                    assert_ne!(face, other_face);
+                    assert_eq!(edges.get(other_face, other_edge), (face, edge));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn not_a_cube() {
+        let net = CubeNet::parse("......").unwrap();
+        assert_eq!(net.fold().unwrap_err(), NetError::NotACube);
+        let net = CubeNet::parse("...\n...").unwrap();
+        assert_eq!(net.fold().unwrap_err(), NetError::NotACube);
+        assert_eq!(
+            CubeNet::parse(".......").unwrap_err(),
+            NetError::FaceSize(7)
+        );
+    }
+
+    #[test]
+    fn render_example() {
+        let input = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
+        let net = CubeNet::parse(input).unwrap();
+        let edges = net.fold().unwrap();
+        assert_eq!(
+            net.render(&edges),
+            "            +-b-+
+            c A f
+            +-d-+
++-a-+ +-a-+ +-a-+
+f B c b C d c D f
++-e-+ +-e-+ +-e-+
+            +-d-+ +-d-+
+            c E f e F a
+            +-b-+ +-b-+
+"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn get_diagonals(&self) -> (DiagonalDirection, DiagonalDirection) {
+        match self {
+            Direction::Up => (DiagonalDirection::UpLeft, DiagonalDirection::UpRight),
+            Direction::Down => (DiagonalDirection::DownRight, DiagonalDirection::DownLeft),
+            Direction::Left => (DiagonalDirection::DownLeft, DiagonalDirection::UpLeft),
+            Direction::Right => (DiagonalDirection::UpRight, DiagonalDirection::DownRight),
+        }
+    }
+
+    pub fn all() -> [Direction; 4] {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+    }
+
+    pub fn get_alignment(&self, other: Direction) -> Self {
+        let count = match self {
+            Direction::Up => 0,
+            Direction::Left => 1,
+            Direction::Down => 2,
+            Direction::Right => 3,
+        };
+
+        other.rotate_clockwise(count)
+    }
+
+    //number of clockwise quarter turns needed to get from self to other
+    pub fn turns_to(&self, other: Direction) -> u32 {
+        (0..4)
+            .find(|&count| self.rotate_clockwise(count) == other)
+            .unwrap()
+    }
+
+    pub fn rotate_clockwise(&self, count: u32) -> Self {
+        let mut current = *self;
+        for _ in 0..count {
+            current = match current {
+                Direction::Up => Direction::Right,
+                Direction::Down => Direction::Left,
+                Direction::Left => Direction::Up,
+                Direction::Right => Direction::Down,
+            }
+        }
+        current
+    }
+
+    pub fn rotate(&self, rotation: Rotation) -> Self {
+        let count = match rotation {
+            Rotation::Left => 3,
+            Rotation::Right => 1,
+        };
+        self.rotate_clockwise(count)
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate_clockwise(2)
+    }
+}
+
+#[derive(Hash, Clone, Copy, Eq, PartialEq, Debug)]
+pub enum DiagonalDirection {
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl DiagonalDirection {
+    pub fn get_directions(&self) -> (Direction, Direction) {
+        match self {
+            DiagonalDirection::DownLeft => (Direction::Down, Direction::Left),
+            DiagonalDirection::DownRight => (Direction::Right, Direction::Down),
+            DiagonalDirection::UpLeft => (Direction::Left, Direction::Up),
+            DiagonalDirection::UpRight => (Direction::Up, Direction::Right),
+        }
+    }
+
+    pub fn all() -> [Self; 4] {
+        [
+            DiagonalDirection::UpLeft,
+            DiagonalDirection::UpRight,
+            DiagonalDirection::DownLeft,
+            DiagonalDirection::DownRight,
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Rotation {
+    Left,
+    Right,
+}
+
+impl Rotation {
+    pub fn new(input: &str) -> Self {
+        match input {
+            "L" => Rotation::Left,
+            "R" => Rotation::Right,
+            _ => panic!("invalid input"),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod cube_net;
+pub mod direction;
+pub mod position;
@@ -0,0 +1,75 @@
+use crate::direction::Direction;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Position {
+    pub fn new(x: i32, y: i32) -> Self {
+        Position { x, y }
+    }
+
+    pub fn wrap_around(&self, width: i32, height: i32) -> Self {
+        Position {
+            x: self.x.rem_euclid(width),
+            y: self.y.rem_euclid(height),
+        }
+    }
+
+    pub fn rotate_clockwise(&self, square_size: i32, count: u32) -> Self {
+        let mut current = *self;
+        for _ in 0..count {
+            current = Position {
+                y: current.x,
+                x: square_size - 1 - current.y,
+            };
+        }
+
+        current
+    }
+
+    pub fn align(&self, direction: Direction, square_size: i32) -> Self {
+        let count = match direction {
+            Direction::Up => 0,
+            Direction::Right => 1,
+            Direction::Down => 2,
+            Direction::Left => 3,
+        };
+
+        self.rotate_clockwise(square_size, count)
+    }
+
+    pub fn step(&self, direction: Direction) -> Self {
+        let (dx, dy) = match direction {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        };
+        let (new_x, new_y) = (self.x + dx, self.y + dy);
+
+        Position { x: new_x, y: new_y }
+    }
+
+    pub fn is_in_bounds(&self, square_size: i32) -> bool {
+        0 <= self.x && self.x < square_size && 0 <= self.y && self.y < square_size
+    }
+
+    pub fn move_to_edge(&self, direction: Direction, square_size: i32) -> Self {
+        match direction {
+            Direction::Up => Position { y: 0, ..*self },
+            Direction::Down => Position {
+                y: square_size - 1,
+                ..*self
+            },
+
+            Direction::Left => Position { x: 0, ..*self },
+            Direction::Right => Position {
+                x: square_size - 1,
+                ..*self
+            },
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{Instruction, OperandType};
+use std::fmt::Write;
+
+fn combo(operand: u64) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4 => "a".to_string(),
+        5 => "b".to_string(),
+        6 => "c".to_string(),
+        _ => format!("<reserved {operand}>"),
+    }
+}
+
+//one line per instruction: address, mnemonic, decoded operand and what it does
+pub fn disassemble(instructions: &[u64]) -> String {
+    let mut output = String::new();
+    for (address, pair) in instructions.chunks(2).enumerate() {
+        let address = address * 2;
+        let Some(instruction) = Instruction::from_opcode(pair[0]) else {
+            writeln!(output, "{address:>3}: <invalid opcode {}>", pair[0]).unwrap();
+            continue;
+        };
+        let Some(&operand) = pair.get(1) else {
+            writeln!(output, "{address:>3}: {instruction:?} <missing operand>").unwrap();
+            continue;
+        };
+
+        let decoded = match instruction.operand_type() {
+            OperandType::Literal => operand.to_string(),
+            OperandType::Combo => combo(operand),
+        };
+        let effect = match instruction {
+            Instruction::Adv => format!("a = a >> {decoded}"),
+            Instruction::Bxl => format!("b = b ^ {decoded}"),
+            Instruction::Bst => format!("b = {decoded} % 8"),
+            Instruction::Jnz => format!("if a != 0 goto {decoded}"),
+            Instruction::Bxc => "b = b ^ c".to_string(),
+            Instruction::Out => format!("output {decoded} % 8"),
+            Instruction::Bdv => format!("b = a >> {decoded}"),
+            Instruction::Cdv => format!("c = a >> {decoded}"),
+        };
+        let mnemonic = format!("{instruction:?}").to_lowercase();
+        writeln!(output, "{address:>3}: {mnemonic} {decoded:<4} ; {effect}").unwrap();
+    }
+
+    output
+}
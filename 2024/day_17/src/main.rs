@@ -1,4 +1,8 @@
-use std::{fs::read_to_string, time::Instant};
+mod disassembler;
+mod search;
+
+use disassembler::disassemble;
+use std::{env, fs::read_to_string, time::Instant};
 use winnow::{
     ascii::dec_uint,
     combinator::{repeat, repeat_till},
@@ -22,19 +26,7 @@ struct Cpu {
 impl Cpu {
     fn get_instruction(&self) -> Option<Instruction> {
         let opcode = *self.instructions.get(self.program_counter)?;
-        let instruction = match opcode {
-            0 => Instruction::Adv,
-            1 => Instruction::Bxl,
-            2 => Instruction::Bst,
-            3 => Instruction::Jnz,
-            4 => Instruction::Bxc,
-            5 => Instruction::Out,
-            6 => Instruction::Bdv,
-            7 => Instruction::Cdv,
-            _ => return None,
-        };
-
-        Some(instruction)
+        Instruction::from_opcode(opcode)
     }
 
     fn get_operand(&self, instruction: Instruction) -> Option<u64> {
@@ -122,36 +114,9 @@ impl Cpu {
             }
         }
     }
-
-    fn find_a(&self, target: &[u64], current: u64) -> Vec<u64> {
-        let last = match target.last() {
-            Some(x) => *x,
-            None => return vec![current],
-        };
-
-        (0..8)
-            .map(|b| current * 8 + b)
-            .filter(|&a| {
-                let new_state = State {
-                    register_a: a,
-                    register_b: 0,
-                    register_c: 0,
-                };
-                let mut new_cpu = Cpu {
-                    state: new_state,
-                    instructions: self.instructions.clone(),
-                    program_counter: 0,
-                };
-
-                let out = new_cpu.next_output();
-                out == Some(last)
-            })
-            .flat_map(|a| self.find_a(&target[..target.len() - 1], a))
-            .collect()
-    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Instruction {
     Adv,
     Bxl,
@@ -164,6 +129,22 @@ enum Instruction {
 }
 
 impl Instruction {
+    fn from_opcode(opcode: u64) -> Option<Self> {
+        let output = match opcode {
+            0 => Self::Adv,
+            1 => Self::Bxl,
+            2 => Self::Bst,
+            3 => Self::Jnz,
+            4 => Self::Bxc,
+            5 => Self::Out,
+            6 => Self::Bdv,
+            7 => Self::Cdv,
+            _ => return None,
+        };
+
+        Some(output)
+    }
+
     fn operand_type(self) -> OperandType {
         match self {
             Self::Adv | Self::Bst | Self::Out | Self::Bdv | Self::Cdv => OperandType::Combo,
@@ -172,6 +153,7 @@ impl Instruction {
     }
 }
 
+#[derive(PartialEq, Eq)]
 enum OperandType {
     Literal,
     Combo,
@@ -186,7 +168,7 @@ fn all_numbers(mut input: &str) -> Vec<u64> {
     .unwrap()
 }
 
-fn solve(path: &str) -> (String, u64) {
+fn parse(path: &str) -> Cpu {
     let input = read_to_string(path).unwrap();
     let nums = all_numbers(&input);
     let (register_a, register_b, register_c) = (nums[0], nums[1], nums[2]);
@@ -196,12 +178,16 @@ fn solve(path: &str) -> (String, u64) {
         register_b,
         register_c,
     };
-    let mut cpu = Cpu {
+    Cpu {
         state,
         instructions,
         program_counter: 0,
-    };
+    }
+}
 
+fn solve(path: &str) -> (String, u64) {
+    let mut cpu = parse(path);
+    let state = cpu.state;
     let output_1 = cpu
         .run()
         .into_iter()
@@ -209,7 +195,7 @@ fn solve(path: &str) -> (String, u64) {
         .collect::<Vec<_>>()
         .join(",");
 
-    let output_2 = cpu.find_a(&cpu.instructions, 0)[0];
+    let output_2 = cpu.find_a(&cpu.instructions).unwrap();
     let state = State {
         register_a: output_2,
         ..state
@@ -225,6 +211,9 @@ fn solve(path: &str) -> (String, u64) {
 
 fn main() {
     let now = Instant::now();
+    if env::args().any(|arg| arg == "--disassemble") {
+        print!("{}", disassemble(&parse("input").instructions));
+    }
     let (output_1, output_2) = solve("input");
     println!("part 1: {output_1} part 2: {output_2}");
     println!("time: {}s", now.elapsed().as_secs_f64())
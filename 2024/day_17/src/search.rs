@@ -0,0 +1,311 @@
+use crate::{Cpu, Instruction, OperandType, State};
+
+//most programs are a single loop: the body outputs once, shifts a right by a
+//constant and jumps back to the start with jnz 0. b and c are recalculated from
+//a before they're read, so each output depends only on the value of a at the
+//start of that pass, and a can be built up `shift` bits at a time from the back
+struct LoopShape {
+    shift: u32,
+}
+
+fn analyse(instructions: &[u64]) -> Option<LoopShape> {
+    if !instructions.len().is_multiple_of(2) {
+        return None;
+    }
+    let body = instructions
+        .chunks(2)
+        .map(|pair| Some((Instruction::from_opcode(pair[0])?, pair[1])))
+        .collect::<Option<Vec<_>>>()?;
+    let (&last, body) = body.split_last()?;
+    if last != (Instruction::Jnz, 0) || body.iter().any(|&(i, _)| i == Instruction::Jnz) {
+        return None;
+    }
+    if body.iter().filter(|&&(i, _)| i == Instruction::Out).count() != 1 {
+        return None;
+    }
+
+    let shifts: Vec<u64> = body
+        .iter()
+        .filter(|&&(i, _)| i == Instruction::Adv)
+        .map(|&(_, operand)| operand)
+        .collect();
+    let shift = match shifts[..] {
+        [shift @ 1..=3] => shift as u32,
+        _ => return None,
+    };
+
+    let mut b_written = false;
+    let mut c_written = false;
+    for &(instruction, operand) in body {
+        let combo = instruction.operand_type() == OperandType::Combo;
+        let reads_b =
+            (combo && operand == 5) || matches!(instruction, Instruction::Bxl | Instruction::Bxc);
+        let reads_c = (combo && operand == 6) || instruction == Instruction::Bxc;
+        if (combo && operand == 7) || (reads_b && !b_written) || (reads_c && !c_written) {
+            return None;
+        }
+
+        match instruction {
+            Instruction::Bxl | Instruction::Bst | Instruction::Bxc | Instruction::Bdv => {
+                b_written = true
+            }
+            Instruction::Cdv => c_written = true,
+            _ => {}
+        }
+    }
+
+    Some(LoopShape { shift })
+}
+
+//a partly known 64 bit value. bits that aren't set in `known` could be anything
+#[derive(Clone, Copy)]
+struct Bits {
+    known: u64,
+    value: u64,
+}
+
+impl Bits {
+    const fn constant(value: u64) -> Self {
+        Self { known: !0, value }
+    }
+
+    const fn xor(self, other: Self) -> Self {
+        let known = self.known & other.known;
+        Self {
+            known,
+            value: (self.value ^ other.value) & known,
+        }
+    }
+
+    const fn shr(self, n: u64) -> Self {
+        if n >= 64 {
+            return Self::constant(0);
+        }
+        Self {
+            known: (self.known >> n) | !(!0 >> n),
+            value: self.value >> n,
+        }
+    }
+
+    const fn low_3(self) -> Self {
+        Self {
+            known: self.known | !7,
+            value: self.value & 7,
+        }
+    }
+
+    const fn exact(self) -> Option<u64> {
+        if self.known == !0 {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+
+    const fn is_zero(self) -> Option<bool> {
+        if self.value != 0 {
+            Some(false)
+        } else if self.known == !0 {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(PartialEq, Eq)]
+enum Verdict {
+    Conflict,
+    Possible,
+    Match,
+}
+
+const MAX_STEPS: usize = 1_000_000;
+
+//runs the program as far as the known bits allow, stopping as soon as the
+//output can no longer match the target
+fn check(instructions: &[u64], state: State, a: Bits, target: &[u64]) -> Verdict {
+    let mut registers = [
+        a,
+        Bits::constant(state.register_b),
+        Bits::constant(state.register_c),
+    ];
+    let mut program_counter = 0;
+    let mut produced = 0;
+    let mut all_outputs_known = true;
+    for _ in 0..MAX_STEPS {
+        let (Some(&opcode), Some(&operand)) = (
+            instructions.get(program_counter),
+            instructions.get(program_counter + 1),
+        ) else {
+            return if produced != target.len() {
+                Verdict::Conflict
+            } else if all_outputs_known {
+                Verdict::Match
+            } else {
+                Verdict::Possible
+            };
+        };
+        let Some(instruction) = Instruction::from_opcode(opcode) else {
+            return Verdict::Conflict;
+        };
+        let value = match (instruction.operand_type(), operand) {
+            (OperandType::Literal, _) | (OperandType::Combo, 0..=3) => Bits::constant(operand),
+            (OperandType::Combo, 4..=6) => registers[operand as usize - 4],
+            _ => return Verdict::Conflict,
+        };
+
+        let [a, b, c] = &mut registers;
+        match instruction {
+            Instruction::Adv | Instruction::Bdv | Instruction::Cdv => {
+                let Some(shift) = value.exact() else {
+                    return Verdict::Possible;
+                };
+                let shifted = a.shr(shift);
+                match instruction {
+                    Instruction::Adv => *a = shifted,
+                    Instruction::Bdv => *b = shifted,
+                    _ => *c = shifted,
+                }
+            }
+            Instruction::Bxl => *b = b.xor(value),
+            Instruction::Bst => *b = value.low_3(),
+            Instruction::Bxc => *b = b.xor(*c),
+            Instruction::Jnz => match a.is_zero() {
+                Some(true) => {}
+                Some(false) => {
+                    program_counter = operand as usize;
+                    continue;
+                }
+                None => return Verdict::Possible,
+            },
+            Instruction::Out => {
+                let out = value.low_3();
+                let Some(&expected) = target.get(produced) else {
+                    return Verdict::Conflict;
+                };
+                if (out.value ^ expected) & out.known & 7 != 0 {
+                    return Verdict::Conflict;
+                }
+                all_outputs_known &= out.known == !0;
+                produced += 1;
+            }
+        }
+        program_counter += 2;
+    }
+
+    if a.exact().is_some() {
+        Verdict::Conflict
+    } else {
+        Verdict::Possible
+    }
+}
+
+impl Cpu {
+    fn first_output(&self, a: u64) -> Option<u64> {
+        let mut cpu = Cpu {
+            state: State {
+                register_a: a,
+                register_b: 0,
+                register_c: 0,
+            },
+            instructions: self.instructions.clone(),
+            program_counter: 0,
+        };
+        cpu.next_output()
+    }
+
+    //works back from the last output. digits are tried in increasing order and
+    //every solution has the same length, so the first one found is the smallest.
+    //a must stay nonzero until the last pass, or the loop would stop early
+    fn find_a_in_loop(&self, shift: u32, target: &[u64], current: u64) -> Option<u64> {
+        let Some((&last, rest)) = target.split_last() else {
+            return Some(current);
+        };
+
+        (0..1 << shift)
+            .map(|digit| (current << shift) | digit)
+            .filter(|&a| a != 0 || target.len() == 1)
+            .filter(|&a| self.first_output(a) == Some(last))
+            .find_map(|a| self.find_a_in_loop(shift, rest, a))
+    }
+
+    //fixes the bits of a from least significant up, pruning whenever the
+    //partly known value already gives the wrong output
+    fn find_a_by_bits(&self, target: &[u64], a: Bits, bit: u32, length: u32) -> Option<u64> {
+        match check(&self.instructions, self.state, a, target) {
+            Verdict::Conflict => return None,
+            //the output doesn't depend on the bits that are left, so leave them as 0
+            Verdict::Match => return Some(a.value),
+            Verdict::Possible => {}
+        }
+        if bit + 1 >= length {
+            return None;
+        }
+
+        let known = a.known | (1 << bit);
+        [0, 1]
+            .into_iter()
+            .filter_map(|b| {
+                let value = a.value | (b << bit);
+                self.find_a_by_bits(target, Bits { known, value }, bit + 1, length)
+            })
+            .min()
+    }
+
+    pub fn find_a(&self, target: &[u64]) -> Option<u64> {
+        if let Some(shape) = analyse(&self.instructions) {
+            return self.find_a_in_loop(shape.shift, target, 0);
+        }
+
+        //smaller bit lengths always give smaller values, so try them in order.
+        //the top bit is set and everything above it is zero
+        (0..=64).find_map(|length| {
+            let a = if length == 0 {
+                Bits::constant(0)
+            } else {
+                let top = 1 << (length - 1);
+                Bits {
+                    known: !(top - 1),
+                    value: top,
+                }
+            };
+            self.find_a_by_bits(target, a, 0, length)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpu(instructions: &[u64]) -> Cpu {
+        Cpu {
+            state: State {
+                register_a: 2024,
+                register_b: 0,
+                register_c: 0,
+            },
+            instructions: instructions.to_vec(),
+            program_counter: 0,
+        }
+    }
+
+    #[test]
+    fn quine() {
+        let program = [0, 3, 5, 4, 3, 0];
+        assert_eq!(analyse(&program).map(|shape| shape.shift), Some(3));
+        assert_eq!(cpu(&program).find_a(&program), Some(117440));
+    }
+
+    #[test]
+    fn without_a_loop() {
+        //b = a % 8, output b, a = a >> 3, b = (a % 8) ^ 1, output b
+        let program = [2, 4, 5, 5, 0, 3, 2, 4, 1, 1, 5, 5];
+        assert!(analyse(&program).is_none());
+        let cpu = cpu(&program);
+        assert_eq!(cpu.find_a(&[5, 2]), Some(0o35));
+        assert_eq!(cpu.find_a(&[0, 1]), Some(0));
+        assert_eq!(cpu.find_a(&[5]), None);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::{Connection, Gate, Network};
+use itertools::Itertools;
+use std::fmt::Write;
+
+//what was found while checking one full-adder stage of the ripple-carry adder
+pub struct StageReport {
+    pub bit: usize,
+    pub swaps: Vec<(String, String)>,
+    pub notes: Vec<String>,
+}
+
+//the ideal wiring of stage i is
+//  sum_i   = x_i XOR y_i        carry_i   = x_i AND y_i         (half adder, bit 0 only)
+//  a_i     = x_i XOR y_i        b_i       = x_i AND y_i
+//  z_i     = a_i XOR c_(i-1)    d_i       = a_i AND c_(i-1)     c_i = b_i OR d_i
+//and the top output bit is the final carry.
+//the gates fed by x_i and y_i can't be miswired at their inputs, so every fault is
+//a pair of swapped outputs, which can be found by walking up the stages in order.
+//b_i and d_i only ever meet at the same OR gate, so a swap involving one of them
+//can be fixed as well by the same swap with the other; either may be reported
+impl Network {
+    fn num_bits(&self) -> usize {
+        self.wires.iter().filter(|s| s.starts_with('x')).count()
+    }
+
+    fn wire(&self, prefix: char, bit: usize) -> usize {
+        self.indices[&format!("{prefix}{bit:02}")]
+    }
+
+    fn find_gate(&self, gate: Gate, x: usize, y: usize) -> Option<usize> {
+        self.connections.iter().position(|&c| match c {
+            Connection::Gate(g, a, b) => g == gate && ((a, b) == (x, y) || (a, b) == (y, x)),
+            Connection::Literal(_) => false,
+        })
+    }
+
+    //finds the gate of this type that reads from x, and returns its other input
+    fn find_partner(&self, gate: Gate, x: usize) -> Option<usize> {
+        self.connections.iter().find_map(|&c| match c {
+            Connection::Gate(g, a, b) if g == gate && a == x => Some(b),
+            Connection::Gate(g, a, b) if g == gate && b == x => Some(a),
+            _ => None,
+        })
+    }
+
+    fn swap_outputs(&mut self, x: usize, y: usize, report: &mut StageReport) {
+        self.connections.swap(x, y);
+        report
+            .swaps
+            .push((self.wires[x].clone(), self.wires[y].clone()));
+    }
+
+    fn missing(&self, gate: Gate, x: usize, y: usize) -> String {
+        format!(
+            "no {gate:?} gate reads from both {} and {}",
+            self.wires[x], self.wires[y]
+        )
+    }
+
+    //makes sure the gate currently writing `output` writes `expected` instead,
+    //swapping the two outputs if it doesn't
+    fn expect_output(&mut self, output: usize, expected: usize, report: &mut StageReport) {
+        if output == expected {
+            return;
+        }
+        report.notes.push(format!(
+            "{} should be written by the gate that writes {}",
+            self.wires[expected], self.wires[output]
+        ));
+        self.swap_outputs(output, expected, report)
+    }
+
+    //finds the gate of this type reading both x and y. if there isn't one then one
+    //of them has been swapped: whichever one such a gate does read is right, and
+    //its other input is the wire the wrong one should have been. the first of
+    //those that doesn't make a loop wins. returns the gate and its fixed inputs
+    fn locate(
+        &mut self,
+        gate: Gate,
+        x: usize,
+        y: usize,
+        report: &mut StageReport,
+    ) -> Result<(usize, usize, usize), String> {
+        if let Some(found) = self.find_gate(gate, x, y) {
+            return Ok((found, x, y));
+        }
+        report.notes.push(self.missing(gate, x, y));
+        for (wrong, right) in [(x, y), (y, x)] {
+            let Some(other) = self.find_partner(gate, right) else {
+                continue;
+            };
+            self.connections.swap(wrong, other);
+            if self.run().is_none() {
+                self.connections.swap(wrong, other);
+                continue;
+            }
+            report
+                .swaps
+                .push((self.wires[wrong].clone(), self.wires[other].clone()));
+            let (x, y) = if wrong == x { (other, y) } else { (x, other) };
+            return Ok((self.find_gate(gate, x, y).unwrap(), x, y));
+        }
+        Err(format!(
+            "neither {} nor {} feeds a {gate:?} gate that can be fixed",
+            self.wires[x], self.wires[y]
+        ))
+    }
+
+    fn check_half_adder(&mut self) -> Result<(StageReport, usize), String> {
+        let mut report = StageReport {
+            bit: 0,
+            swaps: vec![],
+            notes: vec![],
+        };
+        let x = self.wire('x', 0);
+        let y = self.wire('y', 0);
+        let sum = self
+            .find_gate(Gate::Xor, x, y)
+            .ok_or_else(|| self.missing(Gate::Xor, x, y))?;
+        self.expect_output(sum, self.wire('z', 0), &mut report);
+        let carry = self
+            .find_gate(Gate::And, x, y)
+            .ok_or_else(|| self.missing(Gate::And, x, y))?;
+
+        Ok((report, carry))
+    }
+
+    fn check_full_adder(
+        &mut self,
+        bit: usize,
+        carry: usize,
+    ) -> Result<(StageReport, usize), String> {
+        let mut report = StageReport {
+            bit,
+            swaps: vec![],
+            notes: vec![],
+        };
+        let x = self.wire('x', bit);
+        let y = self.wire('y', bit);
+        let a = self
+            .find_gate(Gate::Xor, x, y)
+            .ok_or_else(|| self.missing(Gate::Xor, x, y))?;
+        let (sum, a, carry) = self.locate(Gate::Xor, a, carry, &mut report)?;
+        self.expect_output(sum, self.wire('z', bit), &mut report);
+
+        let b = self
+            .find_gate(Gate::And, x, y)
+            .ok_or_else(|| self.missing(Gate::And, x, y))?;
+        let (d, _, _) = self.locate(Gate::And, a, carry, &mut report)?;
+        let (next_carry, _, _) = self.locate(Gate::Or, b, d, &mut report)?;
+
+        Ok((report, next_carry))
+    }
+
+    //rewires the network into a ripple-carry adder, returning a report for each stage
+    pub fn repair(&mut self) -> Result<Vec<StageReport>, String> {
+        let num_bits = self.num_bits();
+        let (report, mut carry) = self.check_half_adder()?;
+        let mut output = vec![report];
+        for bit in 1..num_bits {
+            let (report, next_carry) = self.check_full_adder(bit, carry)?;
+            output.push(report);
+            carry = next_carry;
+        }
+
+        let mut report = StageReport {
+            bit: num_bits,
+            swaps: vec![],
+            notes: vec![],
+        };
+        self.expect_output(carry, self.wire('z', num_bits), &mut report);
+        output.push(report);
+        Ok(output)
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut output = String::from("digraph network {\n    rankdir=LR;\n");
+        for (i, wire) in self.wires.iter().enumerate().sorted_by_key(|(_, s)| *s) {
+            match self.connections[i] {
+                Connection::Literal(_) => {
+                    writeln!(output, "    {wire} [shape=box];").unwrap();
+                }
+                Connection::Gate(gate, x, y) => {
+                    let shape = if wire.starts_with('z') {
+                        "doubleoctagon"
+                    } else {
+                        "ellipse"
+                    };
+                    writeln!(
+                        output,
+                        "    {wire} [label=\"{wire}\\n{gate:?}\", shape={shape}];"
+                    )
+                    .unwrap();
+                    writeln!(output, "    {} -> {wire};", self.wires[x]).unwrap();
+                    writeln!(output, "    {} -> {wire};", self.wires[y]).unwrap();
+                }
+            }
+        }
+        output.push_str("}\n");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //a correct adder of this many bits, with the outputs of each pair of wires swapped
+    fn adder(bits: usize, swaps: &[(&str, &str)]) -> String {
+        let mut literals = vec![];
+        let mut gates = vec![];
+        for i in 0..bits {
+            literals.push(format!("x{i:02}: {}", i % 2));
+            literals.push(format!("y{i:02}: {}", i % 3 / 2));
+            let carry = if i + 1 == bits {
+                format!("z{bits:02}")
+            } else {
+                format!("c{i:02}")
+            };
+            if i == 0 {
+                gates.push(("x00 XOR y00".to_string(), "z00".to_string()));
+                gates.push(("x00 AND y00".to_string(), carry));
+                continue;
+            }
+            let previous = format!("c{:02}", i - 1);
+            gates.push((format!("x{i:02} XOR y{i:02}"), format!("a{i:02}")));
+            gates.push((format!("x{i:02} AND y{i:02}"), format!("b{i:02}")));
+            gates.push((format!("a{i:02} XOR {previous}"), format!("z{i:02}")));
+            gates.push((format!("a{i:02} AND {previous}"), format!("d{i:02}")));
+            gates.push((format!("b{i:02} OR d{i:02}"), carry));
+        }
+        for (_, output) in &mut gates {
+            for &(x, y) in swaps {
+                if output == x {
+                    *output = y.to_string()
+                } else if output == y {
+                    *output = x.to_string()
+                }
+            }
+        }
+        let gates: Vec<String> = gates
+            .into_iter()
+            .map(|(inputs, output)| format!("{inputs} -> {output}"))
+            .collect();
+        format!("{}\n\n{}", literals.join("\n"), gates.join("\n"))
+    }
+
+    #[test]
+    fn finds_known_swaps() {
+        let swaps = [
+            ("z03", "d03"),
+            ("a05", "b05"),
+            ("d01", "a06"),
+            ("c02", "b07"),
+            ("b04", "b08"),
+        ];
+        let mut network = Network::parse(&adder(10, &swaps));
+        assert!(!network.is_addition());
+        let reports = network.repair().unwrap();
+        assert!(network.is_addition());
+
+        let mut found: Vec<(String, String)> = reports
+            .into_iter()
+            .flat_map(|r| r.swaps)
+            .map(|(x, y)| if x < y { (x, y) } else { (y, x) })
+            .collect();
+        found.sort();
+        let mut expected: Vec<(String, String)> = swaps
+            .iter()
+            .map(|&(x, y)| if x < y { (x, y) } else { (y, x) })
+            .map(|(x, y)| (x.to_string(), y.to_string()))
+            .collect();
+        expected.sort();
+        assert_eq!(found, expected);
+
+        let mut correct = Network::parse(&adder(10, &[]));
+        let reports = correct.repair().unwrap();
+        assert!(reports.iter().all(|r| r.swaps.is_empty()));
+    }
+
+    #[test]
+    fn dot() {
+        let network = Network::parse("x00: 1\ny00: 0\n\nx00 XOR y00 -> a00\nx00 AND a00 -> z00");
+        assert_eq!(
+            network.to_dot(),
+            "digraph network {
+    rankdir=LR;
+    a00 [label=\"a00\\nXor\", shape=ellipse];
+    x00 -> a00;
+    y00 -> a00;
+    x00 [shape=box];
+    y00 [shape=box];
+    z00 [label=\"z00\\nAnd\", shape=doubleoctagon];
+    x00 -> z00;
+    a00 -> z00;
+}
+"
+        );
+    }
+}
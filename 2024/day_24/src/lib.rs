@@ -1,5 +1,7 @@
+mod adder;
+
 use itertools::Itertools;
-use std::{collections::HashMap, fs::read_to_string, mem};
+use std::{collections::HashMap, fs::read_to_string};
 use winnow::{
     ascii::{alphanumeric0, dec_uint, line_ending, till_line_ending},
     combinator::{alt, separated},
@@ -12,7 +14,7 @@ enum Connection {
     Gate(Gate, usize, usize),
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Gate {
     And,
     Or,
@@ -157,15 +159,6 @@ impl Network {
         Some(output)
     }
 
-    fn swap(&mut self, x: &str, y: &str) {
-        let x = self.indices[x];
-        let y = self.indices[y];
-        let l = x.min(y);
-        let u = x.max(y);
-        let (left, right) = self.connections.split_at_mut(u);
-        mem::swap(&mut left[l], right.first_mut().unwrap())
-    }
-
     fn is_addition(&self) -> bool {
         let outputs = match self.run() {
             Some(v) => v,
@@ -187,25 +180,6 @@ impl Network {
             .map(|(i, _)| outputs[i])
             .fold(0, |acc, b| acc * 2 + b as u64)
     }
-
-    fn find_swaps(&mut self) {
-        for x in 0..self.wires.len() {
-            for y in x + 1..self.wires.len() {
-                let x = self.wires[x].clone();
-                let y = self.wires[y].clone();
-                if x.starts_with('x') | x.starts_with('y') | y.starts_with('x') | y.starts_with('y')
-                {
-                    continue;
-                }
-                self.swap(&x, &y);
-                if self.is_addition() {
-                    println!("{x} {y}");
-                }
-
-                self.swap(&x, &y)
-            }
-        }
-    }
 }
 
 fn index_of<'a>(
@@ -228,21 +202,29 @@ pub fn solve(path: &str) -> (u64, String) {
     let outputs = network.run().unwrap();
     let ouptut_1 = network.get_output(&outputs, 'z');
 
-    network.swap("z39", "tnc");
-    network.swap("fhg", "z17");
-    network.swap("z10", "vcf");
-
-    network.find_swaps();
+    let reports = network.repair().unwrap();
+    for report in &reports {
+        for note in &report.notes {
+            println!("bit {}: {note}", report.bit);
+        }
+    }
+    assert!(network.is_addition());
 
-    let output_2 = ["z39", "tnc", "fhg", "z17", "z10", "vcf", "dvb", "fsq"]
-        .into_iter()
+    let output_2 = reports
+        .iter()
+        .flat_map(|r| &r.swaps)
+        .flat_map(|(x, y)| [x, y])
         .sorted()
         .join(",");
 
-    let outputs = network.run().unwrap();
-    let x = network.get_output(&outputs, 'x');
-    let y = network.get_output(&outputs, 'y');
-    println!("{}", x + y);
-
     (ouptut_1, output_2)
 }
+
+pub fn to_dot(path: &str, repaired: bool) -> String {
+    let input = read_to_string(path).unwrap();
+    let mut network = Network::parse(input.trim());
+    if repaired {
+        network.repair().unwrap();
+    }
+    network.to_dot()
+}
@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use santas_little_helpers::union_find::UnionFind;
 use std::{fs::read_to_string, time::Instant};
 
 #[derive(Clone, Copy)]
@@ -14,40 +15,6 @@ impl Point {
     }
 }
 
-struct UnionFind {
-    parents: Vec<usize>,
-}
-
-impl UnionFind {
-    fn find(&mut self, x: usize) -> usize {
-        if self.parents[x] == x {
-            return x;
-        }
-        let output = self.find(self.parents[x]);
-        self.parents[x] = output;
-        output
-    }
-
-    fn join(&mut self, x: usize, y: usize) {
-        let x_root = self.find(x);
-        let y_root = self.find(y);
-        self.parents[x_root] = y_root
-    }
-
-    fn set_sizes(&mut self) -> Vec<usize> {
-        let mut output = vec![0; self.parents.len()];
-        for i in 0..self.parents.len() {
-            let x = self.parents[i];
-            output[self.find(x)] += 1;
-        }
-        output
-    }
-
-    fn all_connected(&mut self) -> bool {
-        (0..self.parents.len()).map(|x| self.find(x)).all_equal()
-    }
-}
-
 fn solve(input: &str) -> (usize, u64) {
     let elements: Vec<Point> = input
         .lines()
@@ -61,24 +28,26 @@ fn solve(input: &str) -> (usize, u64) {
         .map(Point)
         .collect();
     let num_points = elements.len();
-    let mut union_find = UnionFind {
-        parents: (0..num_points).collect(),
-    };
+    let mut union_find = UnionFind::new(num_points);
     for (x, y) in (0..num_points)
         .tuple_combinations()
         .k_smallest_by_key(1000, |&(x, y)| elements[x].distance_squared(elements[y]))
     {
-        union_find.join(x, y);
+        union_find.union(x, y);
     }
-    let output_1 = union_find.set_sizes().into_iter().k_largest(3).product();
+    let output_1 = union_find
+        .component_sizes()
+        .into_iter()
+        .k_largest(3)
+        .product();
 
     let mut output_2 = None;
     for (x, y) in (0..num_points)
         .tuple_combinations()
         .sorted_unstable_by_key(|&(x, y)| elements[x].distance_squared(elements[y]))
     {
-        union_find.join(x, y);
-        if union_find.all_connected() {
+        union_find.union(x, y);
+        if union_find.num_components() == 1 {
             output_2 = Some(elements[x].0[0] * elements[y].0[0]);
             break;
         }
@@ -0,0 +1,426 @@
+use crate::hex::Hex;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+//which cells touch which. only live cells and their neighbours get looked at,
+//so topologies can be unbounded
+pub trait Topology {
+    type Cell: Copy + Eq + Hash;
+
+    fn neighbours(&self, cell: Self::Cell) -> Vec<Self::Cell>;
+
+    //every cell, for topologies small enough to list. rules that bring cells
+    //with no live neighbours to life need it
+    fn cells(&self) -> Option<Vec<Self::Cell>> {
+        None
+    }
+}
+
+//bit n of each mask is set if a cell with n live neighbours is born or survives
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rule {
+    birth: u64,
+    survival: u64,
+}
+
+impl Rule {
+    //conway's: born with 3, survives with 2 or 3
+    pub const LIFE: Self = Self {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+    };
+
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let mask = |counts: &[usize]| counts.iter().fold(0, |acc, n| acc | 1 << n);
+        Self {
+            birth: mask(birth),
+            survival: mask(survival),
+        }
+    }
+
+    //the usual `B3/S23` notation
+    pub fn parse(input: &str) -> Option<Self> {
+        let (birth, survival) = input.split_once('/')?;
+        let digits = |s: &str| {
+            s.chars()
+                .map(|c| c.to_digit(10).map(|d| d as usize))
+                .collect::<Option<Vec<_>>>()
+        };
+        Some(Self::new(
+            &digits(birth.strip_prefix('B')?)?,
+            &digits(survival.strip_prefix('S')?)?,
+        ))
+    }
+
+    pub const fn next(self, alive: bool, neighbours: usize) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        neighbours < 64 && mask & 1 << neighbours != 0
+    }
+}
+
+#[derive(Clone)]
+pub struct Automaton<T: Topology> {
+    pub topology: T,
+    pub rule: Rule,
+    live: HashSet<T::Cell>,
+    //alive whatever the rule says
+    pinned: Vec<T::Cell>,
+    generation: usize,
+}
+
+impl<T: Topology> Automaton<T> {
+    pub fn new(topology: T, rule: Rule, live: impl IntoIterator<Item = T::Cell>) -> Self {
+        Self {
+            topology,
+            rule,
+            live: live.into_iter().collect(),
+            pinned: vec![],
+            generation: 0,
+        }
+    }
+
+    pub fn pin(&mut self, cells: impl IntoIterator<Item = T::Cell>) {
+        for cell in cells {
+            self.live.insert(cell);
+            self.pinned.push(cell)
+        }
+    }
+
+    pub fn live(&self) -> &HashSet<T::Cell> {
+        &self.live
+    }
+
+    pub fn population(&self) -> usize {
+        self.live.len()
+    }
+
+    pub const fn generation(&self) -> usize {
+        self.generation
+    }
+
+    //whether anything changed
+    pub fn step(&mut self) -> bool {
+        let mut counts: HashMap<T::Cell, usize> = HashMap::new();
+        for &cell in &self.live {
+            for neighbour in self.topology.neighbours(cell) {
+                *counts.entry(neighbour).or_default() += 1
+            }
+        }
+
+        let mut candidates: HashSet<T::Cell> = counts.keys().copied().collect();
+        candidates.extend(&self.live);
+        if self.rule.birth & 1 != 0 {
+            let all = self
+                .topology
+                .cells()
+                .expect("cells born with no live neighbours need a finite topology");
+            candidates.extend(all)
+        }
+
+        let mut next: HashSet<T::Cell> = candidates
+            .into_iter()
+            .filter(|cell| {
+                let count = counts.get(cell).copied().unwrap_or(0);
+                self.rule.next(self.live.contains(cell), count)
+            })
+            .collect();
+        next.extend(&self.pinned);
+
+        self.generation += 1;
+        let changed = next != self.live;
+        self.live = next;
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    //the generations it took for nothing to change
+    pub fn run_until_stable(&mut self) -> usize {
+        let start = self.generation;
+        while self.step() {}
+        self.generation - start - 1
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Neighbourhood {
+    //the four sharing an edge
+    VonNeumann,
+    //the eight sharing an edge or a corner
+    Moore,
+}
+
+impl Neighbourhood {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Self::VonNeumann => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Self::Moore => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+fn rectangle(width: usize, height: usize) -> Vec<(usize, usize)> {
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .collect()
+}
+
+//a rectangle with nothing beyond its edges
+#[derive(Clone, Copy, Debug)]
+pub struct Flat {
+    pub width: usize,
+    pub height: usize,
+    pub neighbourhood: Neighbourhood,
+}
+
+impl Flat {
+    //each cell is a power of two in reading order, so only up to 64 cells
+    pub fn biodiversity(&self, live: &HashSet<(usize, usize)>) -> Option<u64> {
+        (self.width * self.height <= 64)
+            .then(|| live.iter().map(|&(x, y)| 1 << (y * self.width + x)).sum())
+    }
+}
+
+impl Topology for Flat {
+    type Cell = (usize, usize);
+
+    fn neighbours(&self, (x, y): Self::Cell) -> Vec<Self::Cell> {
+        self.neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(|&(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+            .filter(|&(x, y)| x < self.width && y < self.height)
+            .collect()
+    }
+
+    fn cells(&self) -> Option<Vec<Self::Cell>> {
+        Some(rectangle(self.width, self.height))
+    }
+}
+
+//a rectangle whose opposite edges meet
+#[derive(Clone, Copy, Debug)]
+pub struct Torus {
+    pub width: usize,
+    pub height: usize,
+    pub neighbourhood: Neighbourhood,
+}
+
+impl Topology for Torus {
+    type Cell = (usize, usize);
+
+    fn neighbours(&self, (x, y): Self::Cell) -> Vec<Self::Cell> {
+        self.neighbourhood
+            .offsets()
+            .iter()
+            .map(|&(dx, dy)| {
+                (
+                    (x + self.width).wrapping_add_signed(dx) % self.width,
+                    (y + self.height).wrapping_add_signed(dy) % self.height,
+                )
+            })
+            .collect()
+    }
+
+    fn cells(&self) -> Option<Vec<Self::Cell>> {
+        Some(rectangle(self.width, self.height))
+    }
+}
+
+//square grids of odd size nested inside each other's middle cell, which
+//itself doesn't exist. level - 1 is the one outside, level + 1 the one inside
+#[derive(Clone, Copy, Debug)]
+pub struct Recursive {
+    pub size: usize,
+    //the outermost and innermost levels there are, or None for no end either way
+    pub depth: Option<(i32, i32)>,
+}
+
+impl Recursive {
+    //the outermost and innermost levels with anything alive
+    pub fn levels(live: &HashSet<(i32, usize, usize)>) -> Option<(i32, i32)> {
+        let levels = live.iter().map(|&(level, _, _)| level);
+        Some((levels.clone().min()?, levels.max()?))
+    }
+
+    pub fn biodiversity(&self, live: &HashSet<(i32, usize, usize)>, level: i32) -> Option<u64> {
+        (self.size * self.size <= 64).then(|| {
+            live.iter()
+                .filter(|&&(l, _, _)| l == level)
+                .map(|&(_, x, y)| 1 << (y * self.size + x))
+                .sum()
+        })
+    }
+
+    fn has_level(&self, level: i32) -> bool {
+        self.depth
+            .is_none_or(|(outer, inner)| (outer..=inner).contains(&level))
+    }
+}
+
+impl Topology for Recursive {
+    type Cell = (i32, usize, usize);
+
+    fn neighbours(&self, (level, x, y): Self::Cell) -> Vec<Self::Cell> {
+        let (n, mid) = (self.size, self.size / 2);
+        let mut output = vec![];
+        for (dx, dy) in Neighbourhood::VonNeumann.offsets() {
+            let (Some(nx), Some(ny)) = (x.checked_add_signed(*dx), y.checked_add_signed(*dy))
+            else {
+                //off the left or top: the square next to the middle one level out
+                output.push((
+                    level - 1,
+                    mid.wrapping_add_signed(*dx),
+                    mid.wrapping_add_signed(*dy),
+                ));
+                continue;
+            };
+            if nx == n || ny == n {
+                output.push((
+                    level - 1,
+                    mid.wrapping_add_signed(*dx),
+                    mid.wrapping_add_signed(*dy),
+                ));
+            } else if (nx, ny) == (mid, mid) {
+                //the whole facing edge of the level inside
+                let edge = (0..n).map(|i| match (dx, dy) {
+                    (1, 0) => (0, i),
+                    (-1, 0) => (n - 1, i),
+                    (0, 1) => (i, 0),
+                    _ => (i, n - 1),
+                });
+                output.extend(edge.map(|(x, y)| (level + 1, x, y)))
+            } else {
+                output.push((level, nx, ny))
+            }
+        }
+        output.retain(|&(level, _, _)| self.has_level(level));
+        output
+    }
+
+    fn cells(&self) -> Option<Vec<Self::Cell>> {
+        let (outer, inner) = self.depth?;
+        let mid = self.size / 2;
+        let squares = rectangle(self.size, self.size)
+            .into_iter()
+            .filter(|&square| square != (mid, mid));
+        Some(
+            (outer..=inner)
+                .flat_map(|level| squares.clone().map(move |(x, y)| (level, x, y)))
+                .collect(),
+        )
+    }
+}
+
+//hexes that each touch six others
+#[derive(Clone, Copy, Debug)]
+pub struct Hexagonal;
+
+impl Topology for Hexagonal {
+    type Cell = Hex;
+
+    fn neighbours(&self, cell: Hex) -> Vec<Hex> {
+        cell.neighbours().to_vec()
+    }
+}
+
+//any neighbours at all, listed per cell
+#[derive(Clone, Debug)]
+pub struct Graph(pub Vec<Vec<usize>>);
+
+impl Topology for Graph {
+    type Cell = usize;
+
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        self.0[cell].clone()
+    }
+
+    fn cells(&self) -> Option<Vec<usize>> {
+        Some((0..self.0.len()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blinker() {
+        assert_eq!(Rule::parse("B3/S23"), Some(Rule::LIFE));
+        let topology = Torus {
+            width: 5,
+            height: 5,
+            neighbourhood: Neighbourhood::Moore,
+        };
+        let mut life = Automaton::new(topology, Rule::LIFE, [(0, 2), (4, 2), (1, 2)]);
+        life.step();
+        assert_eq!(life.live(), &HashSet::from([(0, 1), (0, 2), (0, 3)]));
+        life.step();
+        assert_eq!(life.population(), 3);
+        assert!(life.live().contains(&(4, 2)));
+    }
+
+    #[test]
+    fn recursive_neighbours() {
+        let topology = Recursive {
+            size: 5,
+            depth: None,
+        };
+        let count = |cell| topology.neighbours(cell).len();
+        //the puzzle's examples
+        assert_eq!(count((0, 3, 3)), 4);
+        assert_eq!(count((1, 1, 1)), 4);
+        assert_eq!(count((1, 3, 0)), 4);
+        assert_eq!(count((1, 3, 2)), 8);
+        let mut n = topology.neighbours((1, 3, 2));
+        n.sort();
+        assert_eq!(
+            n,
+            [
+                (1, 3, 1),
+                (1, 3, 3),
+                (1, 4, 2),
+                (2, 4, 0),
+                (2, 4, 1),
+                (2, 4, 2),
+                (2, 4, 3),
+                (2, 4, 4)
+            ]
+        );
+        assert_eq!(topology.neighbours((0, 0, 0)).len(), 4);
+        assert!(topology.neighbours((0, 0, 0)).contains(&(-1, 1, 2)));
+
+        let bounded = Recursive {
+            size: 5,
+            depth: Some((0, 1)),
+        };
+        assert_eq!(bounded.neighbours((0, 0, 0)).len(), 2);
+        assert_eq!(bounded.neighbours((1, 3, 2)).len(), 3);
+        assert_eq!(bounded.cells().map(|c| c.len()), Some(48));
+        assert_eq!(topology.cells(), None);
+
+        let big = Flat {
+            width: 9,
+            height: 8,
+            neighbourhood: Neighbourhood::Moore,
+        };
+        assert_eq!(big.biodiversity(&HashSet::from([(0, 0)])), None);
+        assert_eq!(
+            bounded.biodiversity(&HashSet::from([(1, 1, 0)]), 1),
+            Some(2)
+        );
+    }
+}
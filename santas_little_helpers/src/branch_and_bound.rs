@@ -0,0 +1,169 @@
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::hash::Hash;
+use std::ops::AddAssign;
+
+//a maximisation problem searched depth first. the score of a path is the sum
+//of what each move gains along it
+pub trait Problem {
+    type State;
+    type Key: Hash + Eq;
+
+    //the states reachable in one move, each with the score the move gains.
+    //promising moves first makes the bound bite sooner
+    fn moves(&self, state: &Self::State) -> Vec<(Self::State, u64)>;
+
+    //at least as much as any path from here could still gain
+    fn bound(&self, state: &Self::State) -> u64;
+
+    //states with equal keys have the same futures, so reaching one with no more
+    //score than before can be dropped. None turns this off for the state
+    fn key(&self, _state: &Self::State) -> Option<Self::Key> {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Stats {
+    pub expanded: u64,
+    pub pruned_by_bound: u64,
+    pub dominated: u64,
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, other: Self) {
+        self.expanded += other.expanded;
+        self.pruned_by_bound += other.pruned_by_bound;
+        self.dominated += other.dominated;
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes expanded, {} cut by the bound, {} dominated",
+            self.expanded, self.pruned_by_bound, self.dominated
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Solution {
+    pub score: u64,
+    pub stats: Stats,
+}
+
+struct Search<'a, P: Problem> {
+    problem: &'a P,
+    best: u64,
+    seen: HashMap<P::Key, u64>,
+    stats: Stats,
+}
+
+impl<P: Problem> Search<'_, P> {
+    fn visit(&mut self, state: &P::State, score: u64) {
+        self.best = self.best.max(score);
+        if score + self.problem.bound(state) <= self.best {
+            self.stats.pruned_by_bound += 1;
+            return;
+        }
+        if let Some(key) = self.problem.key(state) {
+            match self.seen.get_mut(&key) {
+                Some(previous) if *previous >= score => {
+                    self.stats.dominated += 1;
+                    return;
+                }
+                Some(previous) => *previous = score,
+                None => {
+                    self.seen.insert(key, score);
+                }
+            }
+        }
+
+        self.stats.expanded += 1;
+        for (next, gain) in self.problem.moves(state) {
+            self.visit(&next, score + gain)
+        }
+    }
+}
+
+pub fn maximise<P: Problem>(problem: &P, start: P::State) -> Solution {
+    let mut search = Search {
+        problem,
+        best: 0,
+        seen: HashMap::new(),
+        stats: Stats::default(),
+    };
+    search.visit(&start, 0);
+    Solution {
+        score: search.best,
+        stats: search.stats,
+    }
+}
+
+//independent problems solved in parallel, results in the same order
+pub fn maximise_all<P>(runs: Vec<(P, P::State)>) -> Vec<Solution>
+where
+    P: Problem + Send,
+    P::State: Send,
+{
+    runs.into_par_iter()
+        .map(|(problem, start)| maximise(&problem, start))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //0/1 knapsack: state is (next item, capacity left)
+    struct Knapsack {
+        items: Vec<(u64, u64)>,
+    }
+
+    impl Problem for Knapsack {
+        type State = (usize, u64);
+        type Key = (usize, u64);
+
+        fn moves(&self, &(i, capacity): &Self::State) -> Vec<(Self::State, u64)> {
+            let Some(&(weight, value)) = self.items.get(i) else {
+                return vec![];
+            };
+            let mut output = vec![];
+            if weight <= capacity {
+                output.push(((i + 1, capacity - weight), value))
+            }
+            output.push(((i + 1, capacity), 0));
+            output
+        }
+
+        fn bound(&self, &(i, _): &Self::State) -> u64 {
+            self.items[i.min(self.items.len())..]
+                .iter()
+                .map(|&(_, v)| v)
+                .sum()
+        }
+
+        fn key(&self, &state: &Self::State) -> Option<Self::Key> {
+            Some(state)
+        }
+    }
+
+    #[test]
+    fn knapsack() {
+        let problem = Knapsack {
+            items: vec![(12, 4), (2, 2), (1, 1), (1, 2), (4, 10)],
+        };
+        let solution = maximise(&problem, (0, 15));
+        assert_eq!(solution.score, 15);
+        assert!(solution.stats.expanded > 0);
+
+        let all = maximise_all(vec![
+            (Knapsack { items: problem.items.clone() }, (0, 15)),
+            (Knapsack { items: problem.items.clone() }, (0, 1)),
+        ]);
+        assert_eq!(all.iter().map(|s| s.score).collect::<Vec<_>>(), [15, 2]);
+    }
+}
@@ -0,0 +1,218 @@
+use std::{array, ops::RangeInclusive};
+
+//axis-aligned box over the integer lattice. each axis is the half-open range min..max,
+//so a unit cube at p is min = p, max = p + 1
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Cuboid<const N: usize> {
+    pub min: [i64; N],
+    pub max: [i64; N],
+}
+
+impl<const N: usize> Cuboid<N> {
+    pub const fn new(min: [i64; N], max: [i64; N]) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_inclusive(ranges: [RangeInclusive<i64>; N]) -> Self {
+        let min = array::from_fn(|i| *ranges[i].start());
+        let max = array::from_fn(|i| *ranges[i].end() + 1);
+        Self { min, max }
+    }
+
+    pub fn unit(point: [i64; N]) -> Self {
+        let max = point.map(|x| x + 1);
+        Self { min: point, max }
+    }
+
+    fn side_lengths(&self) -> [u64; N] {
+        array::from_fn(|i| (self.max[i] - self.min[i]).max(0) as u64)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|i| self.min[i] >= self.max[i])
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        (0..N).all(|i| (self.min[i]..self.max[i]).contains(&point[i]))
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.side_lengths().into_iter().product()
+    }
+
+    pub fn surface_area(&self) -> u64 {
+        let lengths = self.side_lengths();
+        if lengths.contains(&0) {
+            return 0;
+        }
+
+        (0..N)
+            .map(|axis| {
+                (0..N)
+                    .filter(|&i| i != axis)
+                    .map(|i| lengths[i])
+                    .product::<u64>()
+            })
+            .sum::<u64>()
+            * 2
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = array::from_fn(|i| self.min[i].max(other.min[i]));
+        let max = array::from_fn(|i| self.max[i].min(other.max[i]));
+        let output = Self { min, max };
+        (!output.is_empty()).then_some(output)
+    }
+
+    //splits self \ other into at most 2N disjoint pieces by peeling off a slab
+    //below and above the intersection along each axis in turn
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        let Some(intersection) = self.intersection(other) else {
+            return vec![*self];
+        };
+
+        let mut remaining = *self;
+        let mut output = vec![];
+        for axis in 0..N {
+            if remaining.min[axis] < intersection.min[axis] {
+                let mut below = remaining;
+                below.max[axis] = intersection.min[axis];
+                output.push(below);
+            }
+            if intersection.max[axis] < remaining.max[axis] {
+                let mut above = remaining;
+                above.min[axis] = intersection.max[axis];
+                output.push(above);
+            }
+            remaining.min[axis] = intersection.min[axis];
+            remaining.max[axis] = intersection.max[axis];
+        }
+
+        output
+    }
+
+    //area of the face shared by two disjoint cuboids that touch
+    fn contact_area(&self, other: &Self) -> u64 {
+        (0..N)
+            .filter(|&axis| self.max[axis] == other.min[axis] || other.max[axis] == self.min[axis])
+            .map(|axis| {
+                (0..N)
+                    .filter(|&i| i != axis)
+                    .map(|i| (self.max[i].min(other.max[i]) - self.min[i].max(other.min[i])).max(0))
+                    .product::<i64>() as u64
+            })
+            .sum()
+    }
+}
+
+//union of pairwise disjoint cuboids
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct CuboidSet<const N: usize> {
+    cuboids: Vec<Cuboid<N>>,
+}
+
+impl<const N: usize> CuboidSet<N> {
+    pub const fn new() -> Self {
+        Self { cuboids: vec![] }
+    }
+
+    pub fn cuboids(&self) -> &[Cuboid<N>] {
+        &self.cuboids
+    }
+
+    pub fn add(&mut self, cuboid: Cuboid<N>) {
+        if cuboid.is_empty() {
+            return;
+        }
+        self.subtract(cuboid);
+        self.cuboids.push(cuboid);
+    }
+
+    pub fn subtract(&mut self, cuboid: Cuboid<N>) {
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|c| c.difference(&cuboid))
+            .collect();
+    }
+
+    pub fn intersect(&mut self, cuboid: Cuboid<N>) {
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .filter_map(|c| c.intersection(&cuboid))
+            .collect();
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        self.cuboids.iter().any(|c| c.contains(point))
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.cuboids.iter().map(Cuboid::volume).sum()
+    }
+
+    pub fn surface_area(&self) -> u64 {
+        let total: u64 = self.cuboids.iter().map(Cuboid::surface_area).sum();
+        let hidden: u64 = self
+            .cuboids
+            .iter()
+            .enumerate()
+            .flat_map(|(i, first)| {
+                self.cuboids[i + 1..]
+                    .iter()
+                    .map(move |second| first.contact_area(second))
+            })
+            .sum();
+
+        total - hidden * 2
+    }
+}
+
+impl<const N: usize> FromIterator<Cuboid<N>> for CuboidSet<N> {
+    fn from_iter<T: IntoIterator<Item = Cuboid<N>>>(iter: T) -> Self {
+        let mut output = Self::new();
+        for cuboid in iter {
+            output.add(cuboid);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_squares() {
+        let mut set = CuboidSet::new();
+        set.add(Cuboid::new([0, 0], [2, 2]));
+        set.add(Cuboid::new([1, 1], [3, 3]));
+        assert_eq!(set.volume(), 7);
+        assert_eq!(set.surface_area(), 12);
+
+        set.subtract(Cuboid::unit([1, 1]));
+        assert_eq!(set.volume(), 6);
+        assert_eq!(set.surface_area(), 16);
+        assert!(!set.contains([1, 1]));
+        assert!(set.contains([2, 1]));
+    }
+
+    #[test]
+    fn hollow_cube() {
+        let mut set = CuboidSet::new();
+        set.add(Cuboid::new([0, 0, 0], [3, 3, 3]));
+        set.subtract(Cuboid::unit([1, 1, 1]));
+        assert_eq!(set.volume(), 26);
+        assert_eq!(set.surface_area(), 54 + 6);
+    }
+
+    #[test]
+    fn tesseract() {
+        let set: CuboidSet<4> = [Cuboid::unit([0; 4]), Cuboid::unit([1, 0, 0, 0])]
+            .into_iter()
+            .collect();
+        assert_eq!(set.volume(), 2);
+        assert_eq!(set.surface_area(), 8 + 8 - 2);
+    }
+}
@@ -0,0 +1,492 @@
+use std::fmt::{self, Display};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinaryOp {
+    const ALL: [Self; 4] = [Self::Add, Self::Sub, Self::Mul, Self::Div];
+
+    const fn symbol(self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Sub => '-',
+            Self::Mul => '*',
+            Self::Div => '/',
+        }
+    }
+
+    fn from_symbol(c: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.symbol() == c)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Expr {
+    Number(i64),
+    Variable(String),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub expected: &'static str,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {} at byte {}", self.expected, self.position)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EvalError {
+    UnknownVariable(String),
+    Overflow,
+    DivisionByZero,
+    //an integer division that leaves a remainder
+    Inexact,
+    //the variable being solved for gets multiplied by itself or divided by
+    NotLinear,
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownVariable(name) => write!(f, "unknown variable {name}"),
+            Self::Overflow => write!(f, "overflow"),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::Inexact => write!(f, "integer division with a remainder"),
+            Self::NotLinear => write!(f, "expression isn't linear in the unknown"),
+        }
+    }
+}
+
+//binding powers for each operator, higher binds tighter. all operators are left
+//associative, and ones missing from the table are rejected by the parser
+#[derive(Clone, Copy, Debug)]
+pub struct Precedence([Option<u8>; 4]);
+
+impl Precedence {
+    //usual arithmetic: * and / before + and -
+    pub const STANDARD: Self = Self([Some(1), Some(1), Some(2), Some(2)]);
+    //everything evaluated left to right
+    pub const FLAT: Self = Self([Some(1); 4]);
+
+    //levels are given from loosest to tightest
+    pub fn new(levels: &[&[BinaryOp]]) -> Self {
+        let mut output = [None; 4];
+        for (level, ops) in levels.iter().enumerate() {
+            for &op in *ops {
+                output[op as usize] = Some(level as u8 + 1)
+            }
+        }
+        Self(output)
+    }
+
+    fn power(&self, op: BinaryOp) -> Option<u8> {
+        self.0[op as usize]
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Expr, ParseError> {
+        let mut parser = Parser {
+            input,
+            position: 0,
+            precedence: *self,
+        };
+        let output = parser.expression(0)?;
+        if parser.peek().is_some() {
+            return parser.error("an operator or the end of the input");
+        }
+        Ok(output)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+    precedence: Precedence,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, expected: &'static str) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.position,
+            expected,
+        })
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+        self.input[self.position..].chars().next()
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &str {
+        let rest = &self.input[self.position..];
+        let length = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.position += length;
+        &rest[..length]
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let output = self.expression(0)?;
+                if self.peek() != Some(')') {
+                    return self.error("a closing bracket");
+                }
+                self.position += 1;
+                Ok(output)
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let start = self.position;
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                digits.parse().map(Expr::Number).or_else(|_| {
+                    self.position = start;
+                    self.error("a number that fits in 64 bits")
+                })
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                Ok(Expr::Variable(name.to_string()))
+            }
+            _ => self.error("a number, variable or bracket"),
+        }
+    }
+
+    //pratt parsing: keeps folding operators into the left hand side while they
+    //bind at least as tightly as `min`
+    fn expression(&mut self, min: u8) -> Result<Expr, ParseError> {
+        let mut output = self.atom()?;
+        while let Some(op) = self.peek().and_then(BinaryOp::from_symbol) {
+            let Some(power) = self.precedence.power(op) else {
+                return self.error("an operator from the precedence table");
+            };
+            if power < min {
+                break;
+            }
+            self.position += 1;
+            let right = self.expression(power + 1)?;
+            output = Expr::Binary(op, Box::new(output), Box::new(right));
+        }
+        Ok(output)
+    }
+}
+
+//fully bracketed, so it parses back the same under any precedence
+impl Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Variable(name) => write!(f, "{name}"),
+            Expr::Binary(op, left, right) => write!(f, "({left} {} {right})", op.symbol()),
+        }
+    }
+}
+
+//the arithmetic an expression can be evaluated in
+pub trait Scalar: Copy + Sized {
+    fn from_integer(n: i64) -> Result<Self, EvalError>;
+    fn apply(self, op: BinaryOp, other: Self) -> Result<Self, EvalError>;
+}
+
+macro_rules! integer_scalar {
+    ($t:ty) => {
+        impl Scalar for $t {
+            fn from_integer(n: i64) -> Result<Self, EvalError> {
+                n.try_into().map_err(|_| EvalError::Overflow)
+            }
+
+            fn apply(self, op: BinaryOp, other: Self) -> Result<Self, EvalError> {
+                match op {
+                    BinaryOp::Add => self.checked_add(other).ok_or(EvalError::Overflow),
+                    BinaryOp::Sub => self.checked_sub(other).ok_or(EvalError::Overflow),
+                    BinaryOp::Mul => self.checked_mul(other).ok_or(EvalError::Overflow),
+                    BinaryOp::Div => {
+                        if other == 0 {
+                            Err(EvalError::DivisionByZero)
+                        } else if self.checked_rem(other) != Some(0) {
+                            Err(EvalError::Inexact)
+                        } else {
+                            self.checked_div(other).ok_or(EvalError::Overflow)
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+integer_scalar!(i64);
+integer_scalar!(u64);
+integer_scalar!(i128);
+
+const fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+//always in lowest terms with a positive denominator
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    pub fn new(numerator: i128, denominator: i128) -> Result<Self, EvalError> {
+        if denominator == 0 {
+            return Err(EvalError::DivisionByZero);
+        }
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Ok(Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+
+    pub const fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub const fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn to_integer(self) -> Option<i128> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self {
+            numerator: n as i128,
+            denominator: 1,
+        }
+    }
+}
+
+impl Scalar for Rational {
+    fn from_integer(n: i64) -> Result<Self, EvalError> {
+        Ok(n.into())
+    }
+
+    fn apply(self, op: BinaryOp, other: Self) -> Result<Self, EvalError> {
+        let mul = |a: i128, b: i128| a.checked_mul(b).ok_or(EvalError::Overflow);
+        let (a, b, c, d) = (
+            self.numerator,
+            self.denominator,
+            other.numerator,
+            other.denominator,
+        );
+        match op {
+            BinaryOp::Add | BinaryOp::Sub => {
+                let left = mul(a, d)?;
+                let right = mul(c, b)?;
+                let numerator = if op == BinaryOp::Add {
+                    left.checked_add(right)
+                } else {
+                    left.checked_sub(right)
+                };
+                Self::new(numerator.ok_or(EvalError::Overflow)?, mul(b, d)?)
+            }
+            BinaryOp::Mul => Self::new(mul(a, c)?, mul(b, d)?),
+            BinaryOp::Div => Self::new(mul(a, d)?, mul(b, c)?),
+        }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_integer() {
+            Some(n) => write!(f, "{n}"),
+            None => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Solution {
+    Value(Rational),
+    //both sides are the same whatever the unknown is
+    Always,
+    //both sides differ by a constant
+    Never,
+}
+
+//coefficient * x + constant
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Linear {
+    coefficient: Rational,
+    constant: Rational,
+}
+
+impl Linear {
+    fn constant(value: Rational) -> Self {
+        Self {
+            coefficient: Rational::ZERO,
+            constant: value,
+        }
+    }
+
+    fn apply(self, op: BinaryOp, other: Self) -> Result<Self, EvalError> {
+        match op {
+            BinaryOp::Add | BinaryOp::Sub => Ok(Self {
+                coefficient: self.coefficient.apply(op, other.coefficient)?,
+                constant: self.constant.apply(op, other.constant)?,
+            }),
+            BinaryOp::Mul | BinaryOp::Div => {
+                let (linear, factor) = if other.coefficient == Rational::ZERO {
+                    (self, other.constant)
+                } else if op == BinaryOp::Mul && self.coefficient == Rational::ZERO {
+                    (other, self.constant)
+                } else {
+                    return Err(EvalError::NotLinear);
+                };
+                Ok(Self {
+                    coefficient: linear.coefficient.apply(op, factor)?,
+                    constant: linear.constant.apply(op, factor)?,
+                })
+            }
+        }
+    }
+}
+
+impl Expr {
+    pub fn evaluate<T: Scalar>(
+        &self,
+        variables: &impl Fn(&str) -> Option<T>,
+    ) -> Result<T, EvalError> {
+        match self {
+            Expr::Number(n) => T::from_integer(*n),
+            Expr::Variable(name) => {
+                variables(name).ok_or_else(|| EvalError::UnknownVariable(name.clone()))
+            }
+            Expr::Binary(op, left, right) => left
+                .evaluate(variables)?
+                .apply(*op, right.evaluate(variables)?),
+        }
+    }
+
+    //replaces every variable that `f` has an expression for
+    pub fn substitute(&self, f: &mut impl FnMut(&str) -> Option<Expr>) -> Expr {
+        match self {
+            Expr::Number(_) => self.clone(),
+            Expr::Variable(name) => f(name).unwrap_or_else(|| self.clone()),
+            Expr::Binary(op, left, right) => Expr::Binary(
+                *op,
+                Box::new(left.substitute(f)),
+                Box::new(right.substitute(f)),
+            ),
+        }
+    }
+
+    fn linear(&self, unknown: &str) -> Result<Linear, EvalError> {
+        match self {
+            Expr::Number(n) => Ok(Linear::constant((*n).into())),
+            Expr::Variable(name) if name == unknown => Ok(Linear {
+                coefficient: 1.into(),
+                constant: Rational::ZERO,
+            }),
+            Expr::Variable(name) => Err(EvalError::UnknownVariable(name.clone())),
+            Expr::Binary(op, left, right) => {
+                left.linear(unknown)?.apply(*op, right.linear(unknown)?)
+            }
+        }
+    }
+
+    //the value of `unknown` that makes both sides equal. every other variable must
+    //have been substituted away
+    pub fn solve_linear(&self, other: &Expr, unknown: &str) -> Result<Solution, EvalError> {
+        let difference = self
+            .linear(unknown)?
+            .apply(BinaryOp::Sub, other.linear(unknown)?)?;
+        if difference.coefficient == Rational::ZERO {
+            return Ok(if difference.constant == Rational::ZERO {
+                Solution::Always
+            } else {
+                Solution::Never
+            });
+        }
+        Rational::ZERO
+            .apply(BinaryOp::Sub, difference.constant)?
+            .apply(BinaryOp::Div, difference.coefficient)
+            .map(Solution::Value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(precedence: Precedence, input: &str) -> i64 {
+        precedence
+            .parse(input)
+            .unwrap()
+            .evaluate(&|_| None)
+            .unwrap()
+    }
+
+    #[test]
+    fn precedence() {
+        use BinaryOp::*;
+        let input = "2 * 3 + (4 * 5)";
+        assert_eq!(evaluate(Precedence::FLAT, input), 26);
+        assert_eq!(evaluate(Precedence::STANDARD, input), 26);
+        assert_eq!(evaluate(Precedence::new(&[&[Mul], &[Add]]), input), 46);
+        assert_eq!(evaluate(Precedence::STANDARD, "10 - 4 - 3"), 3);
+        assert_eq!(
+            evaluate(Precedence::new(&[&[Mul], &[Sub]]), "2 * 10 - 4"),
+            12
+        );
+
+        let expr = Precedence::STANDARD.parse("1 + 2 * x").unwrap();
+        assert_eq!(expr.to_string(), "(1 + (2 * x))");
+        assert_eq!(Precedence::FLAT.parse(&expr.to_string()), Ok(expr));
+        assert!(Precedence::new(&[&[Add]]).parse("1 * 2").is_err());
+        assert!(Precedence::STANDARD.parse("(1 + 2").is_err());
+    }
+
+    #[test]
+    fn exact() {
+        let expr = Precedence::STANDARD.parse("7 / 2").unwrap();
+        assert_eq!(expr.evaluate::<i64>(&|_| None), Err(EvalError::Inexact));
+        assert_eq!(
+            expr.evaluate::<Rational>(&|_| None),
+            Ok(Rational::new(7, 2).unwrap())
+        );
+
+        let left = Precedence::STANDARD.parse("(4 + 2 * (x - 3)) / 4").unwrap();
+        let right = Precedence::STANDARD.parse("150").unwrap();
+        assert_eq!(
+            left.solve_linear(&right, "x"),
+            Ok(Solution::Value(301.into()))
+        );
+        let square = Precedence::STANDARD.parse("x * x").unwrap();
+        assert_eq!(square.solve_linear(&right, "x"), Err(EvalError::NotLinear));
+        let cancelled = Precedence::STANDARD.parse("x - x + 150").unwrap();
+        assert_eq!(cancelled.solve_linear(&right, "x"), Ok(Solution::Always));
+        let shifted = Precedence::STANDARD.parse("x + 1").unwrap();
+        let shifted_more = Precedence::STANDARD.parse("x + 2").unwrap();
+        assert_eq!(
+            shifted.solve_linear(&shifted_more, "x"),
+            Ok(Solution::Never)
+        );
+    }
+}
@@ -0,0 +1,173 @@
+pub use md5::{compute, Context, Digest};
+use rayon::prelude::*;
+use std::collections::VecDeque;
+
+const CHUNK_SIZE: usize = 4096;
+
+pub fn nibble(digest: &Digest, i: usize) -> u8 {
+    let byte = digest.0[i / 2];
+    if i.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0xf
+    }
+}
+
+pub fn starts_with_zeros(digest: &Digest, count: usize) -> bool {
+    (0..count).all(|i| nibble(digest, i) == 0)
+}
+
+//md5 of the salt followed by the decimal index, without going through format!
+pub fn digest(salted: &Context, index: usize) -> Digest {
+    let mut buffer = [0; 20];
+    let mut start = buffer.len();
+    let mut n = index;
+    loop {
+        start -= 1;
+        buffer[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+
+    let mut context = salted.clone();
+    context.consume(&buffer[start..]);
+    context.compute()
+}
+
+pub fn salted(salt: &str) -> Context {
+    let mut output = Context::new();
+    output.consume(salt);
+    output
+}
+
+//rehashes the lowercase hex of the digest `times` times
+pub fn stretch(digest: Digest, times: usize) -> Digest {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut output = digest;
+    for _ in 0..times {
+        let mut hex = [0; 32];
+        for (i, byte) in output.0.into_iter().enumerate() {
+            hex[2 * i] = HEX[byte as usize >> 4];
+            hex[2 * i + 1] = HEX[byte as usize & 0xf];
+        }
+        output = md5::compute(hex);
+    }
+    output
+}
+
+//every index from `start` up whose digest passes the predicate, in order.
+//digests are worked out a chunk at a time in parallel
+pub struct Matches<P> {
+    salted: Context,
+    stretch: usize,
+    predicate: P,
+    next_chunk: usize,
+    found: VecDeque<(usize, Digest)>,
+}
+
+impl<P: Fn(&Digest) -> bool + Sync> Iterator for Matches<P> {
+    type Item = (usize, Digest);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.found.is_empty() {
+            let chunk = self.next_chunk..self.next_chunk + CHUNK_SIZE;
+            self.next_chunk = chunk.end;
+            let found: Vec<(usize, Digest)> = chunk
+                .into_par_iter()
+                .map(|i| (i, stretch(digest(&self.salted, i), self.stretch)))
+                .filter(|(_, d)| (self.predicate)(d))
+                .collect();
+            self.found.extend(found);
+        }
+
+        self.found.pop_front()
+    }
+}
+
+pub fn matches<P: Fn(&Digest) -> bool + Sync>(
+    salt: &str,
+    start: usize,
+    stretch: usize,
+    predicate: P,
+) -> Matches<P> {
+    Matches {
+        salted: salted(salt),
+        stretch,
+        predicate,
+        next_chunk: start,
+        found: VecDeque::new(),
+    }
+}
+
+//the digests for a run of consecutive indices, computed ahead in parallel chunks.
+//indices below the window's start are dropped once the caller moves past them
+pub struct Window {
+    salted: Context,
+    stretch: usize,
+    start: usize,
+    digests: VecDeque<Digest>,
+}
+
+impl Window {
+    pub fn new(salt: &str, stretch: usize) -> Self {
+        Self {
+            salted: salted(salt),
+            stretch,
+            start: 0,
+            digests: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&mut self, index: usize) -> Digest {
+        assert!(index >= self.start, "index {index} has left the window");
+        while index >= self.start + self.digests.len() {
+            let from = self.start + self.digests.len();
+            let chunk: Vec<Digest> = (from..from + CHUNK_SIZE)
+                .into_par_iter()
+                .map(|i| stretch(digest(&self.salted, i), self.stretch))
+                .collect();
+            self.digests.extend(chunk);
+        }
+        self.digests[index - self.start]
+    }
+
+    pub fn advance_to(&mut self, start: usize) {
+        if start <= self.start {
+            return;
+        }
+        let dropped = (start - self.start).min(self.digests.len());
+        self.digests.drain(..dropped);
+        self.start = start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_format() {
+        let salted = salted("abc");
+        for i in [0, 7, 10, 3231929, usize::MAX] {
+            let expected = md5::compute(format!("abc{i}"));
+            assert_eq!(digest(&salted, i), expected);
+        }
+        let expected = md5::compute(format!("{:x}", md5::compute("abc0")));
+        assert_eq!(stretch(digest(&salted, 0), 1), expected);
+    }
+
+    #[test]
+    fn ordered() {
+        let found: Vec<usize> = matches("abc", 5, 0, |d| starts_with_zeros(d, 2))
+            .map(|(i, _)| i)
+            .take(50)
+            .collect();
+        let expected: Vec<usize> = (5..)
+            .filter(|i| format!("{:x}", md5::compute(format!("abc{i}"))).starts_with("00"))
+            .take(50)
+            .collect();
+        assert_eq!(found, expected);
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::ops::{Add, Mul, Sub};
+
+//axial coordinates. the third cube coordinate is s = -q - r. which way q and r
+//point on screen depends on the layout
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+//the six neighbours going round, the same whichever way up the hexes are
+const AXIAL: [Hex; 6] = [
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+    Hex::new(0, 1),
+];
+
+impl Hex {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    pub const fn from_cube((q, r, s): (i32, i32, i32)) -> Option<Self> {
+        if q + r + s == 0 {
+            Some(Self::new(q, r))
+        } else {
+            None
+        }
+    }
+
+    pub const fn cube(self) -> (i32, i32, i32) {
+        (self.q, self.r, -self.q - self.r)
+    }
+
+    //steps from the origin
+    pub const fn length(self) -> i32 {
+        let (q, r, s) = self.cube();
+        (q.abs() + r.abs() + s.abs()) / 2
+    }
+
+    pub const fn distance(self, other: Self) -> i32 {
+        Self::new(self.q - other.q, self.r - other.r).length()
+    }
+
+    pub fn neighbours(self) -> [Self; 6] {
+        AXIAL.map(|d| self + d)
+    }
+
+    //the hexes exactly `radius` away, going round
+    pub fn ring(self, radius: i32) -> Vec<Self> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut output = vec![];
+        let mut current = self + AXIAL[4] * radius;
+        for direction in AXIAL {
+            for _ in 0..radius {
+                output.push(current);
+                current = current + direction
+            }
+        }
+        output
+    }
+
+    //every hex a straight line from here to `other` passes through, both ends
+    //included. the nudge keeps lines along edges from wobbling between sides
+    pub fn line_to(self, other: Self) -> Vec<Self> {
+        let n = self.distance(other);
+        let (a, b) = (self.cube(), other.cube());
+        let lerp = |a: i32, b: i32, t: f64, nudge: f64| a as f64 + (b - a) as f64 * t + nudge;
+        (0..=n)
+            .map(|i| {
+                let t = if n == 0 { 0.0 } else { i as f64 / n as f64 };
+                cube_round(
+                    lerp(a.0, b.0, t, 1e-6),
+                    lerp(a.1, b.1, t, 2e-6),
+                    lerp(a.2, b.2, t, -3e-6),
+                )
+            })
+            .collect()
+    }
+}
+
+//the nearest hex, fixing whichever coordinate was rounded furthest
+fn cube_round(q: f64, r: f64, s: f64) -> Hex {
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs
+    } else if dr > ds {
+        rr = -rq - rs
+    }
+    Hex::new(rq as i32, rr as i32)
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Self;
+
+    fn mul(self, k: i32) -> Self {
+        Self::new(self.q * k, self.r * k)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Layout {
+    //columns of hexes: n, ne, se, s, sw, nw
+    FlatTop,
+    //rows of hexes: e, ne, nw, w, sw, se
+    PointyTop,
+}
+
+impl Layout {
+    //going round clockwise from north or east. r grows southwards in both
+    pub const fn directions(self) -> [(&'static str, Hex); 6] {
+        match self {
+            Self::FlatTop => [
+                ("n", Hex::new(0, -1)),
+                ("ne", Hex::new(1, -1)),
+                ("se", Hex::new(1, 0)),
+                ("s", Hex::new(0, 1)),
+                ("sw", Hex::new(-1, 1)),
+                ("nw", Hex::new(-1, 0)),
+            ],
+            Self::PointyTop => [
+                ("e", Hex::new(1, 0)),
+                ("se", Hex::new(0, 1)),
+                ("sw", Hex::new(-1, 1)),
+                ("w", Hex::new(-1, 0)),
+                ("nw", Hex::new(0, -1)),
+                ("ne", Hex::new(1, -1)),
+            ],
+        }
+    }
+
+    pub fn direction(self, name: &str) -> Option<Hex> {
+        self.directions()
+            .into_iter()
+            .find(|&(n, _)| n == name)
+            .map(|(_, d)| d)
+    }
+
+    //a run of directions with no separators, like `nwwswee`
+    pub fn parse_path(self, input: &str) -> Option<Vec<Hex>> {
+        let mut output = vec![];
+        let mut rest = input;
+        while !rest.is_empty() {
+            let (d, len) = [2, 1]
+                .into_iter()
+                .filter(|&len| len <= rest.len())
+                .find_map(|len| Some((self.direction(rest.get(..len)?)?, len)))?;
+            output.push(d);
+            rest = &rest[len..];
+        }
+        Some(output)
+    }
+
+    //(column, row), with every odd row shoved right for pointy tops or every odd
+    //column shoved down for flat tops
+    pub const fn to_offset(self, hex: Hex) -> (i32, i32) {
+        match self {
+            Self::FlatTop => (hex.q, hex.r + (hex.q - (hex.q & 1)) / 2),
+            Self::PointyTop => (hex.q + (hex.r - (hex.r & 1)) / 2, hex.r),
+        }
+    }
+
+    pub const fn from_offset(self, (column, row): (i32, i32)) -> Hex {
+        match self {
+            Self::FlatTop => Hex::new(column, row - (column - (column & 1)) / 2),
+            Self::PointyTop => Hex::new(column - (row - (row & 1)) / 2, row),
+        }
+    }
+
+    //a character per hex on a brick pattern: pointy rows are staggered by one
+    //space, flat columns by one line
+    pub fn render(self, cells: &HashMap<Hex, char>) -> String {
+        let offsets: Vec<((i32, i32), char)> = cells
+            .iter()
+            .map(|(&h, &c)| (self.to_offset(h), c))
+            .collect();
+        let Some(min_column) = offsets.iter().map(|((c, _), _)| *c).min() else {
+            return String::new();
+        };
+        let min_row = offsets.iter().map(|((_, r), _)| *r).min().unwrap();
+        //start on an even row or column so the staggering stays the same
+        let (min_column, min_row) = (min_column - (min_column & 1), min_row - (min_row & 1));
+
+        let text: HashMap<(usize, usize), char> = offsets
+            .into_iter()
+            .map(|((column, row), c)| {
+                let (column, row) = ((column - min_column) as usize, (row - min_row) as usize);
+                let position = match self {
+                    Self::FlatTop => (2 * column, 2 * row + (column & 1)),
+                    Self::PointyTop => (2 * column + (row & 1), row),
+                };
+                (position, c)
+            })
+            .collect();
+        let left = text.keys().map(|&(x, _)| x).min().unwrap();
+        let top = text.keys().map(|&(_, y)| y).min().unwrap();
+        let width = text.keys().map(|&(x, _)| x).max().unwrap() + 1;
+        let height = text.keys().map(|&(_, y)| y).max().unwrap() + 1;
+        let mut output = String::new();
+        for y in top..height {
+            let line: String = (left..width)
+                .map(|x| text.get(&(x, y)).copied().unwrap_or(' '))
+                .collect();
+            output.push_str(line.trim_end());
+            output.push('\n')
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        let walk = |input: &str| -> i32 {
+            input
+                .split(',')
+                .map(|d| Layout::FlatTop.direction(d).unwrap())
+                .fold(Hex::ORIGIN, |acc, d| acc + d)
+                .length()
+        };
+        assert_eq!(walk("ne,ne,ne"), 3);
+        assert_eq!(walk("ne,ne,sw,sw"), 0);
+        assert_eq!(walk("ne,ne,s,s"), 2);
+        assert_eq!(walk("se,sw,se,sw,sw"), 3);
+
+        let path = Layout::PointyTop.parse_path("nwwswee").unwrap();
+        assert_eq!(
+            path.into_iter().fold(Hex::ORIGIN, |a, d| a + d),
+            Hex::ORIGIN
+        );
+    }
+
+    #[test]
+    fn rings_and_lines() {
+        let centre = Hex::new(2, -1);
+        assert_eq!(centre.ring(0), [centre]);
+        for radius in 1..5 {
+            let ring = centre.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|h| h.distance(centre) == radius));
+        }
+
+        let line = Hex::ORIGIN.line_to(Hex::new(4, -2));
+        assert_eq!(line.len(), 5);
+        assert!(line.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        assert_eq!(Hex::ORIGIN.line_to(Hex::ORIGIN), [Hex::ORIGIN]);
+    }
+
+    #[test]
+    fn offsets() {
+        for layout in [Layout::FlatTop, Layout::PointyTop] {
+            for hex in Hex::new(-1, 3).ring(3) {
+                assert_eq!(layout.from_offset(layout.to_offset(hex)), hex);
+            }
+        }
+        assert_eq!(Hex::from_cube((1, -3, 2)), Some(Hex::new(1, -3)));
+        assert_eq!(Hex::from_cube((1, 1, 1)), None);
+    }
+
+    #[test]
+    fn render() {
+        let mut cells: HashMap<Hex, char> =
+            Hex::ORIGIN.ring(1).into_iter().map(|h| (h, 'o')).collect();
+        cells.insert(Hex::ORIGIN, 'x');
+        assert_eq!(Layout::PointyTop.render(&cells), " o o\no x o\n o o\n");
+        assert_eq!(
+            Layout::FlatTop.render(&cells),
+            "  o\no   o\n  x\no   o\n  o\n"
+        );
+    }
+}
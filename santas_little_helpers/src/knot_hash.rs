@@ -0,0 +1,93 @@
+use rayon::prelude::*;
+
+const SUFFIX: [usize; 5] = [17, 31, 73, 47, 23];
+
+//rather than moving the current position around the circle, the list is rotated
+//so the current position is always at the front, and `start` tracks where the
+//real front has got to
+struct State {
+    numbers: Vec<u8>,
+    skip_size: usize,
+    start: usize,
+}
+
+impl State {
+    fn new() -> Self {
+        Self {
+            numbers: (0..=255).collect(),
+            skip_size: 0,
+            start: 0,
+        }
+    }
+
+    fn update(&mut self, length: usize) {
+        self.numbers[..length].reverse();
+        let rotate_length = (length + self.skip_size) % 256;
+        self.numbers.rotate_left(rotate_length);
+        self.start += 256 - rotate_length;
+        self.start %= 256;
+        self.skip_size += 1;
+    }
+
+    fn into_numbers(mut self) -> Vec<u8> {
+        self.numbers.rotate_left(self.start);
+        self.numbers
+    }
+}
+
+pub fn sparse_hash(lengths: &[usize], rounds: usize) -> Vec<u8> {
+    let mut state = State::new();
+    for _ in 0..rounds {
+        for &length in lengths {
+            state.update(length);
+        }
+    }
+    state.into_numbers()
+}
+
+pub fn dense_hash(sparse: &[u8]) -> [u8; 16] {
+    let mut output = [0; 16];
+    for (x, chunk) in output.iter_mut().zip(sparse.chunks(16)) {
+        *x = chunk.iter().fold(0, |acc, y| acc ^ y);
+    }
+    output
+}
+
+pub fn knot_hash(input: &str) -> [u8; 16] {
+    let lengths: Vec<usize> = input.bytes().map(|b| b as usize).chain(SUFFIX).collect();
+    dense_hash(&sparse_hash(&lengths, 64))
+}
+
+pub fn knot_hashes<S: AsRef<str> + Sync>(inputs: &[S]) -> Vec<[u8; 16]> {
+    inputs.par_iter().map(|s| knot_hash(s.as_ref())).collect()
+}
+
+pub fn hex(hash: &[u8; 16]) -> String {
+    hash.iter().map(|n| format!("{n:02x}")).collect()
+}
+
+//most significant bit first
+pub fn bits(hash: &[u8; 16]) -> [bool; 128] {
+    std::array::from_fn(|i| hash[i / 8] & (0x80 >> (i % 8)) != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples() {
+        assert_eq!(hex(&knot_hash("")), "a2582a3a0e66e6e86e3812dcb672a272");
+        assert_eq!(
+            hex(&knot_hash("AoC 2017")),
+            "33efeb34ea91902bb2f59c9920caa6cd"
+        );
+        assert_eq!(hex(&knot_hash("1,2,3")), "3efbe78a8d82f29979031a4aa0b16a9d");
+        let hashes = knot_hashes(&["", "1,2,4"]);
+        assert_eq!(hex(&hashes[1]), "63960835bcdc130f0b66d7ff4f6a5a8e");
+        assert_eq!(
+            &bits(&hashes[0])[..8],
+            [true, false, true, false, false, false, true, false]
+        );
+    }
+}
@@ -1,3 +1,18 @@
+pub mod automaton;
+pub mod branch_and_bound;
+pub mod cuboids;
+pub mod expression;
+pub mod grid;
+pub mod hex;
+pub mod hash_search;
+pub mod knot_hash;
+pub mod number;
+pub mod particle;
+pub mod search;
+pub mod union_find;
+pub mod value;
+pub mod vector;
+
 #[allow(dead_code)]
 mod lib {
     pub mod positions_and_friends {
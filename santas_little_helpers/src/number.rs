@@ -0,0 +1,35 @@
+pub const fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+//0 if either is
+pub const fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+//the first time a set of cycles of these lengths all line up again
+pub fn lcm_all(lengths: impl IntoIterator<Item = u64>) -> u64 {
+    lengths.into_iter().fold(1, lcm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiples() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm_all([18, 28, 44]), 2772);
+        assert_eq!(lcm_all([]), 1);
+    }
+}
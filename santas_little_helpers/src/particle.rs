@@ -0,0 +1,168 @@
+use crate::vector::Vector;
+use std::collections::{BTreeMap, HashSet};
+
+//moves in whole ticks: velocity picks up the acceleration, then position
+//picks up the velocity
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Particle<const N: usize> {
+    pub position: Vector<N>,
+    pub velocity: Vector<N>,
+    pub acceleration: Vector<N>,
+}
+
+impl<const N: usize> Particle<N> {
+    pub fn step(&mut self) {
+        self.velocity += self.acceleration;
+        self.position += self.velocity
+    }
+
+    //after t ticks twice the position is a t^2 + (2v + a) t + 2p on every axis
+    pub fn coefficients(self) -> [Vector<N>; 3] {
+        [
+            self.acceleration,
+            self.velocity * 2 + self.acceleration,
+            self.position * 2,
+        ]
+    }
+
+    pub fn position_at(self, t: i64) -> Vector<N> {
+        let [a, b, c] = self.coefficients();
+        (a * (t * t) + b * t + c).map(|x| x / 2)
+    }
+
+    //the distance from the origin for large t, as its t^2, t and constant terms.
+    //each axis ends up on the side its first nonzero coefficient points to
+    pub fn long_run_distance(self) -> (i64, i64, i64) {
+        let [a, b, c] = self.coefficients();
+        (0..N)
+            .map(|axis| {
+                let sign = [a[axis], b[axis], c[axis]]
+                    .into_iter()
+                    .find(|&x| x != 0)
+                    .unwrap_or(0)
+                    .signum();
+                (sign * a[axis], sign * b[axis], sign * c[axis])
+            })
+            .fold((0, 0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1, acc.2 + x.2))
+    }
+
+    //the first tick both are in the same place, if they ever are
+    pub fn collision(self, other: Self) -> Option<i64> {
+        let [a, b, c] = self.coefficients();
+        let [oa, ob, oc] = other.coefficients();
+        let (a, b, c) = (a - oa, b - ob, c - oc);
+        let mut candidates: Option<Vec<i64>> = None;
+        for axis in 0..N {
+            //an axis where they always agree rules nothing out
+            let Some(roots) = quadratic_roots(a[axis], b[axis], c[axis]) else {
+                continue;
+            };
+            candidates = Some(match candidates {
+                None => roots,
+                Some(previous) => previous.into_iter().filter(|t| roots.contains(t)).collect(),
+            });
+        }
+        match candidates {
+            None => Some(0),
+            Some(times) => times.into_iter().min(),
+        }
+    }
+}
+
+//the ticks where a t^2 + b t + c is zero, or None if it always is
+pub fn quadratic_roots(a: i64, b: i64, c: i64) -> Option<Vec<i64>> {
+    let (a, b, c) = (a as i128, b as i128, c as i128);
+    let exact = |n: i128, d: i128| (d != 0 && n % d == 0 && n / d >= 0).then(|| (n / d) as i64);
+    let roots = match (a, b, c) {
+        (0, 0, 0) => return None,
+        (0, 0, _) => vec![],
+        (0, _, _) => exact(-c, b).into_iter().collect(),
+        _ => {
+            let discriminant = b * b - 4 * a * c;
+            if discriminant < 0 {
+                vec![]
+            } else {
+                let root = discriminant.isqrt();
+                if root * root != discriminant {
+                    vec![]
+                } else {
+                    let mut roots: Vec<i64> = [-b - root, -b + root]
+                        .into_iter()
+                        .filter_map(|n| exact(n, 2 * a))
+                        .collect();
+                    roots.sort();
+                    roots.dedup();
+                    roots
+                }
+            }
+        }
+    };
+    Some(roots)
+}
+
+//the particles left once every collision has happened, in time order. one
+//that's already been destroyed doesn't take anything else with it later
+pub fn survivors<const N: usize>(particles: &[Particle<N>]) -> Vec<usize> {
+    let mut collisions: BTreeMap<i64, Vec<(usize, usize)>> = BTreeMap::new();
+    for i in 0..particles.len() {
+        for j in i + 1..particles.len() {
+            if let Some(t) = particles[i].collision(particles[j]) {
+                collisions.entry(t).or_default().push((i, j))
+            }
+        }
+    }
+
+    let mut destroyed = HashSet::new();
+    for pairs in collisions.into_values() {
+        let now: HashSet<usize> = pairs
+            .into_iter()
+            .filter(|(i, j)| !destroyed.contains(i) && !destroyed.contains(j))
+            .flat_map(|(i, j)| [i, j])
+            .collect();
+        destroyed.extend(now)
+    }
+    (0..particles.len())
+        .filter(|i| !destroyed.contains(i))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roots() {
+        assert_eq!(quadratic_roots(0, 0, 0), None);
+        assert_eq!(quadratic_roots(0, 0, 3), Some(vec![]));
+        assert_eq!(quadratic_roots(0, 2, -6), Some(vec![3]));
+        assert_eq!(quadratic_roots(1, -5, 6), Some(vec![2, 3]));
+        assert_eq!(quadratic_roots(1, 5, 6), Some(vec![]));
+        assert_eq!(quadratic_roots(1, 0, -2), Some(vec![]));
+        assert_eq!(quadratic_roots(2, -3, 1), Some(vec![1]));
+    }
+
+    #[test]
+    fn collisions() {
+        let particle = |p, v, a| Particle {
+            position: Vector::new([p]),
+            velocity: Vector::new([v]),
+            acceleration: Vector::new([a]),
+        };
+        let mut accelerating = particle(3, 0, 2);
+        let start = accelerating;
+        assert_eq!(start.collision(particle(15, 0, 0)), Some(3));
+        for t in 0..5 {
+            assert_eq!(start.position_at(t), accelerating.position);
+            accelerating.step()
+        }
+        assert_eq!(start.long_run_distance(), (2, 2, 6));
+
+        let particles = [
+            particle(-6, 3, 0),
+            particle(-4, 2, 0),
+            particle(-2, 1, 0),
+            particle(3, -1, 0),
+        ];
+        assert_eq!(survivors(&particles), [3]);
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+//the number of steps from the nearest start to everything reachable
+pub fn bfs<T, I>(
+    starts: impl IntoIterator<Item = T>,
+    mut neighbours: impl FnMut(&T) -> I,
+) -> HashMap<T, usize>
+where
+    T: Clone + Eq + Hash,
+    I: IntoIterator<Item = T>,
+{
+    let mut distances = HashMap::new();
+    let mut frontier = VecDeque::new();
+    for start in starts {
+        if distances.insert(start.clone(), 0).is_none() {
+            frontier.push_back(start);
+        }
+    }
+
+    while let Some(current) = frontier.pop_front() {
+        let distance = distances[&current];
+        for next in neighbours(&current) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                frontier.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line() {
+        let distances = bfs([0, 10], |&x: &i32| {
+            [x - 1, x + 1].into_iter().filter(|x| (0..=10).contains(x))
+        });
+        assert_eq!(distances.len(), 11);
+        assert_eq!(distances[&4], 4);
+        assert_eq!(distances[&7], 3);
+    }
+}
@@ -0,0 +1,293 @@
+use std::{collections::HashMap, hash::Hash};
+
+//disjoint sets over the indices 0..len, joined by size.
+//paths are compressed on find, except when a rollback log is being kept,
+//since compressed paths can't be undone
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    num_components: usize,
+    history: Option<Vec<usize>>,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            num_components: len,
+            history: None,
+        }
+    }
+
+    pub fn with_rollback(len: usize) -> Self {
+        Self {
+            history: Some(vec![]),
+            ..Self::new(len)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    pub fn push(&mut self) -> usize {
+        let output = self.parents.len();
+        self.parents.push(output);
+        self.sizes.push(1);
+        self.num_components += 1;
+        output
+    }
+
+    pub fn root(&self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            x = self.parents[x]
+        }
+        x
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let root = self.root(x);
+        if self.history.is_none() {
+            let mut current = x;
+            while current != root {
+                current = std::mem::replace(&mut self.parents[current], root);
+            }
+        }
+        root
+    }
+
+    //returns false if x and y were already in the same set
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let x_root = self.find(x);
+        let y_root = self.find(y);
+        if x_root == y_root {
+            return false;
+        }
+
+        let (small, large) = if self.sizes[x_root] < self.sizes[y_root] {
+            (x_root, y_root)
+        } else {
+            (y_root, x_root)
+        };
+        self.parents[small] = large;
+        self.sizes[large] += self.sizes[small];
+        self.num_components -= 1;
+        if let Some(history) = &mut self.history {
+            history.push(small)
+        }
+        true
+    }
+
+    pub fn connected(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    pub const fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut output: HashMap<usize, Vec<usize>> = HashMap::new();
+        for x in 0..self.len() {
+            output.entry(self.find(x)).or_default().push(x)
+        }
+        let mut output: Vec<Vec<usize>> = output.into_values().collect();
+        output.sort_unstable();
+        output
+    }
+
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&x| self.parents[x] == x)
+            .map(|x| self.sizes[x])
+            .collect()
+    }
+
+    //the number of unions performed so far, to be passed to rollback later
+    pub fn snapshot(&self) -> usize {
+        self.history
+            .as_ref()
+            .expect("rollback log is not being kept")
+            .len()
+    }
+
+    pub fn rollback(&mut self, snapshot: usize) {
+        let history = self
+            .history
+            .as_mut()
+            .expect("rollback log is not being kept");
+        while history.len() > snapshot {
+            let child = history.pop().unwrap();
+            let parent = self.parents[child];
+            self.sizes[parent] -= self.sizes[child];
+            self.parents[child] = child;
+            self.num_components += 1;
+        }
+    }
+}
+
+//the same structure over arbitrary keys, which are added the first time they're seen
+#[derive(Clone, Debug)]
+pub struct KeyedUnionFind<T> {
+    indices: HashMap<T, usize>,
+    keys: Vec<T>,
+    inner: UnionFind,
+}
+
+impl<T: Hash + Eq + Clone> KeyedUnionFind<T> {
+    pub fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            keys: vec![],
+            inner: UnionFind::new(0),
+        }
+    }
+
+    pub fn with_rollback() -> Self {
+        Self {
+            inner: UnionFind::with_rollback(0),
+            ..Self::new()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn keys(&self) -> &[T] {
+        &self.keys
+    }
+
+    pub fn insert(&mut self, key: T) -> usize {
+        if let Some(&i) = self.indices.get(&key) {
+            return i;
+        }
+        let i = self.inner.push();
+        self.indices.insert(key.clone(), i);
+        self.keys.push(key);
+        i
+    }
+
+    pub fn find(&mut self, key: T) -> &T {
+        let i = self.insert(key);
+        let root = self.inner.find(i);
+        &self.keys[root]
+    }
+
+    pub fn union(&mut self, x: T, y: T) -> bool {
+        let x = self.insert(x);
+        let y = self.insert(y);
+        self.inner.union(x, y)
+    }
+
+    pub fn connected(&mut self, x: T, y: T) -> bool {
+        let x = self.insert(x);
+        let y = self.insert(y);
+        self.inner.connected(x, y)
+    }
+
+    pub fn size(&mut self, key: T) -> usize {
+        let i = self.insert(key);
+        self.inner.size(i)
+    }
+
+    pub const fn num_components(&self) -> usize {
+        self.inner.num_components()
+    }
+
+    pub fn component(&mut self, key: T) -> Vec<T> {
+        let i = self.insert(key);
+        let root = self.inner.find(i);
+        (0..self.len())
+            .filter(|&j| self.inner.find(j) == root)
+            .map(|j| self.keys[j].clone())
+            .collect()
+    }
+
+    pub fn components(&mut self) -> Vec<Vec<T>> {
+        self.inner
+            .components()
+            .into_iter()
+            .map(|v| v.into_iter().map(|i| self.keys[i].clone()).collect())
+            .collect()
+    }
+
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.inner.component_sizes()
+    }
+
+    pub fn snapshot(&self) -> usize {
+        self.inner.snapshot()
+    }
+
+    //undoes unions only. keys inserted since the snapshot stay, as singletons
+    pub fn rollback(&mut self, snapshot: usize) {
+        self.inner.rollback(snapshot)
+    }
+}
+
+impl<T: Hash + Eq + Clone> Default for KeyedUnionFind<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone> FromIterator<T> for KeyedUnionFind<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut output = Self::new();
+        for key in iter {
+            output.insert(key);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollback() {
+        let mut union_find = UnionFind::with_rollback(5);
+        union_find.union(0, 1);
+        let snapshot = union_find.snapshot();
+        union_find.union(1, 2);
+        union_find.union(3, 4);
+        assert_eq!(union_find.size(0), 3);
+        assert_eq!(union_find.num_components(), 2);
+
+        union_find.rollback(snapshot);
+        assert_eq!(
+            union_find.components(),
+            vec![vec![0, 1], vec![2], vec![3], vec![4]]
+        );
+        assert!(!union_find.connected(1, 2));
+    }
+
+    #[test]
+    fn keyed() {
+        let mut union_find: KeyedUnionFind<&str> = ["a", "b", "c"].into_iter().collect();
+        union_find.union("a", "c");
+        union_find.union("d", "e");
+        assert_eq!(union_find.num_components(), 3);
+        assert_eq!(union_find.component("c"), vec!["a", "c"]);
+        let mut sizes = union_find.component_sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 2, 2]);
+    }
+}
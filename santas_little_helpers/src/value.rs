@@ -0,0 +1,303 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+//a json-like tree. objects keep their keys in the order they were written
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Value {
+    Number(i64),
+    String(String),
+    List(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub expected: &'static str,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {} at byte {}", self.expected, self.position)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, expected: &'static str) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.position,
+            expected,
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.input[self.position..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.position += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, expected: &'static str) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(expected)
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        match self.peek() {
+            Some('[') => self.list(),
+            Some('{') => self.object(),
+            Some('"') => self.string().map(Value::String),
+            Some('-' | '0'..='9') => self.number(),
+            _ => self.error("a value"),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, ParseError> {
+        let rest = &self.input[self.position..];
+        let sign = rest.starts_with('-') as usize;
+        let length = sign
+            + rest[sign..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - sign);
+        let Ok(n) = rest[..length].parse() else {
+            return self.error("a number that fits in 64 bits");
+        };
+        self.position += length;
+        Ok(Value::Number(n))
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect('"', "a string")?;
+        let mut output = String::new();
+        let mut chars = self.input[self.position..].chars();
+        while let Some(c) = chars.next() {
+            self.position += c.len_utf8();
+            match c {
+                '"' => return Ok(output),
+                '\\' => {
+                    let Some(escaped) = chars.next() else { break };
+                    self.position += escaped.len_utf8();
+                    output.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        '"' | '\\' | '/' => escaped,
+                        _ => return self.error("a known escape"),
+                    });
+                }
+                c => output.push(c),
+            }
+        }
+        self.error("a closing quote")
+    }
+
+    //the items between `open` and `close`, separated by commas
+    fn sequence<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.expect(open, "an opening bracket")?;
+        let mut output = vec![];
+        if self.eat(close) {
+            return Ok(output);
+        }
+        loop {
+            output.push(item(self)?);
+            if self.eat(close) {
+                return Ok(output);
+            }
+            self.expect(',', "a comma or closing bracket")?;
+        }
+    }
+
+    fn list(&mut self) -> Result<Value, ParseError> {
+        self.sequence('[', ']', Self::value).map(Value::List)
+    }
+
+    fn object(&mut self) -> Result<Value, ParseError> {
+        self.sequence('{', '}', |parser| {
+            let key = parser.string()?;
+            parser.expect(':', "a colon")?;
+            Ok((key, parser.value()?))
+        })
+        .map(Value::Object)
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input, position: 0 };
+        let output = parser.value()?;
+        if parser.peek().is_some() {
+            return parser.error("the end of the input");
+        }
+        Ok(output)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            '\r' => write!(f, "\\r")?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+//compact, with no whitespace, so lists come out the way they're written in puzzle inputs
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{n}"),
+            Value::String(s) => write_string(f, s),
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl Value {
+    pub fn as_number(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    //list items or object values
+    pub fn children(&self) -> Box<dyn Iterator<Item = &Value> + '_> {
+        match self {
+            Value::List(items) => Box::new(items.iter()),
+            Value::Object(entries) => Box::new(entries.iter().map(|(_, v)| v)),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    //bottom up: each node is given the results for its children
+    pub fn fold<T>(&self, f: &mut impl FnMut(&Value, Vec<T>) -> T) -> T {
+        let children = self.children().map(|child| child.fold(f)).collect();
+        f(self, children)
+    }
+
+    //top down, in document order. returning false skips the node's children
+    pub fn visit(&self, f: &mut impl FnMut(&Value) -> bool) {
+        if f(self) {
+            for child in self.children() {
+                child.visit(f)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for input in [
+            "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+            "[[]]",
+            r#"{"a":[-1,{"b":"red"}],"c":"x\"y\\z"}"#,
+            "-12",
+        ] {
+            let value: Value = input.parse().unwrap();
+            assert_eq!(value.to_string(), input);
+        }
+
+        let value: Value = " { \"a\" : [ 1 , 2 ] } ".parse().unwrap();
+        assert_eq!(
+            value.get("a"),
+            Some(&Value::List(vec![Value::Number(1), Value::Number(2)]))
+        );
+        assert_eq!("[1,]".parse::<Value>().unwrap_err().position, 3);
+        assert!("[1".parse::<Value>().is_err());
+        assert!("[1] 2".parse::<Value>().is_err());
+    }
+
+    #[test]
+    fn fold() {
+        let value: Value = r#"[1,{"c":"red","b":2},3]"#.parse().unwrap();
+        let sum = value.fold(&mut |v, children: Vec<i64>| {
+            v.as_number().unwrap_or(0) + children.iter().sum::<i64>()
+        });
+        assert_eq!(sum, 6);
+
+        let mut strings = vec![];
+        value.visit(&mut |v| {
+            strings.extend(v.as_str().map(String::from));
+            true
+        });
+        assert_eq!(strings, ["red"]);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{
+    array,
+    iter::Sum,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+};
+
+//a point or offset on the integer lattice
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Vector<const N: usize>(pub [i64; N]);
+
+impl<const N: usize> Vector<N> {
+    pub const ZERO: Self = Self([0; N]);
+
+    pub const fn new(coordinates: [i64; N]) -> Self {
+        Self(coordinates)
+    }
+
+    //one step along an axis
+    pub fn unit(axis: usize) -> Self {
+        Self(array::from_fn(|i| (i == axis) as i64))
+    }
+
+    //comma separated, spaces allowed
+    pub fn parse(input: &str) -> Option<Self> {
+        let coordinates: Vec<i64> = input
+            .split(',')
+            .map(|x| x.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        Some(Self(coordinates.try_into().ok()?))
+    }
+
+    pub fn map(self, f: impl FnMut(i64) -> i64) -> Self {
+        Self(self.0.map(f))
+    }
+
+    pub fn zip_with(self, other: Self, mut f: impl FnMut(i64, i64) -> i64) -> Self {
+        Self(array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+
+    pub fn signum(self) -> Self {
+        self.map(i64::signum)
+    }
+
+    pub fn dot(self, other: Self) -> i64 {
+        (0..N).map(|i| self.0[i] * other.0[i]).sum()
+    }
+
+    pub fn manhattan(self) -> i64 {
+        self.0.iter().map(|x| x.abs()).sum()
+    }
+
+    pub fn chebyshev(self) -> i64 {
+        self.0.iter().map(|x| x.abs()).max().unwrap_or(0)
+    }
+
+    //the 2N sharing a face
+    pub fn neighbours(self) -> Vec<Self> {
+        (0..N)
+            .flat_map(|axis| [self + Self::unit(axis), self - Self::unit(axis)])
+            .collect()
+    }
+
+    //the 3^N - 1 sharing a face, edge or corner
+    pub fn adjacent(self) -> Vec<Self> {
+        let mut output = vec![self];
+        for axis in 0..N {
+            output = output
+                .into_iter()
+                .flat_map(|v| [-1, 0, 1].map(|d| v + Self::unit(axis) * d))
+                .collect();
+        }
+        output.retain(|&v| v != self);
+        output
+    }
+}
+
+impl<const N: usize> Default for Vector<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const N: usize> From<[i64; N]> for Vector<N> {
+    fn from(coordinates: [i64; N]) -> Self {
+        Self(coordinates)
+    }
+}
+
+impl<const N: usize> Index<usize> for Vector<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Vector<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> Add for Vector<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl<const N: usize> Sub for Vector<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+impl<const N: usize> Mul<i64> for Vector<N> {
+    type Output = Self;
+
+    fn mul(self, k: i64) -> Self {
+        self.map(|x| x * k)
+    }
+}
+
+impl<const N: usize> Neg for Vector<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|x| -x)
+    }
+}
+
+impl<const N: usize> AddAssign for Vector<N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other
+    }
+}
+
+impl<const N: usize> SubAssign for Vector<N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other
+    }
+}
+
+impl<const N: usize> Sum for Vector<N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, v| acc + v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_and_norms() {
+        let a = Vector::new([1, -2, 3]);
+        let b = Vector::parse("4, 5,-6").unwrap();
+        assert_eq!(a + b, Vector::new([5, 3, -3]));
+        assert_eq!(b - a, Vector::new([3, 7, -9]));
+        assert_eq!(-a * 2, Vector::new([-2, 4, -6]));
+        assert_eq!(a.dot(b), -24);
+        assert_eq!((b - a).manhattan(), 19);
+        assert_eq!((b - a).chebyshev(), 9);
+        assert_eq!([a, b].into_iter().sum::<Vector<3>>(), a + b);
+        assert_eq!(Vector::<2>::parse("1,2,3"), None);
+    }
+
+    #[test]
+    fn neighbours() {
+        let v = Vector::new([0, 0, 0, 0]);
+        assert_eq!(v.neighbours().len(), 8);
+        assert!(v.neighbours().iter().all(|n| n.manhattan() == 1));
+        let adjacent = v.adjacent();
+        assert_eq!(adjacent.len(), 80);
+        assert!(adjacent.iter().all(|n| n.chebyshev() == 1));
+        assert_eq!(Vector::new([7]).adjacent(), [Vector([6]), Vector([8])]);
+    }
+}